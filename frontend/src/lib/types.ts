@@ -7,6 +7,27 @@ export interface RegisterValue {
 	value: string;
 	category: string;
 	valid: boolean;
+	raw_bytes?: number[];
+}
+
+export interface VectorRegisterValue {
+	name: string;
+	value: string;
+	category: string;
+	valid: boolean;
+	raw_bytes?: number[];
+}
+
+export interface FpuState {
+	control_word: number;
+	status_word: number;
+	tag_word: number;
+	instruction_pointer: string;
+	instruction_selector: number;
+	data_pointer: string;
+	data_selector: number;
+	st: VectorRegisterValue[];
+	valid: boolean;
 }
 
 export interface StructuredContext {
@@ -16,6 +37,8 @@ export interface StructuredContext {
 	flags: RegisterValue[];
 	debug: RegisterValue[];
 	other: RegisterValue[];
+	vector: VectorRegisterValue[];
+	fpu?: FpuState;
 	architecture: string;
 }
 
@@ -40,15 +63,25 @@ export interface SystemInfoRaw {
 
 export interface SystemInfoData {
 	os?: string;
+	os_display: string;
+	wine: boolean;
+	os_release_name?: string;
 	cpu_info?: string;
 	raw?: SystemInfoRaw;
 	debug?: string;
 }
 
+export interface MiscInfoData {
+	process_id?: number;
+	process_integrity_level?: number;
+	process_integrity_level_name?: 'Untrusted' | 'Low' | 'Medium' | 'High' | 'System';
+}
+
 // === Exception Types ===
 export interface ExceptionRecord {
 	exception_code: number;
 	exception_flags: number;
+	exception_flags_decoded: string[];
 	exception_record: string; // SafeU64 -> string
 	exception_address: string; // SafeU64 -> string
 	number_parameters: number;
@@ -58,11 +91,36 @@ export interface ExceptionRecord {
 export interface ExceptionStreamRaw {
 	thread_id: number;
 	exception_record: ExceptionRecord;
+	thread_context_rva: number;
+	thread_context_size: number;
+}
+
+export interface ShadowStackEntry {
+	return_address: string;
+	module_name?: string;
+}
+
+export interface NearestSymbol {
+	name: string;
+	offset: number;
 }
 
 export interface ExceptionData {
 	crash_reason?: string;
 	crash_address?: string; // SafeU64 -> string
+	faulting_instruction?: string; // SafeU64 -> string
+	normalized_address?: string;
+	address_note?: string;
+	context_architecture_matches: boolean;
+	in_page_error_status?: string;
+	heap_corruption: boolean;
+	heap_failure_type?: string;
+	breakpoint_message?: string;
+	faulting_registers: string[];
+	has_nested_exception: boolean;
+	nested_depth: number;
+	shadow_stack?: ShadowStackEntry[];
+	nearest_symbol?: NearestSymbol;
 	thread_id: number;
 	context?: StructuredContext;
 	raw?: ExceptionStreamRaw;
@@ -75,12 +133,29 @@ export interface StackInfo {
 	start_address: string;
 	memory_size: number;
 	memory_data: number[];
+	stack_captured_bytes: number;
+	stack_coverage_percent: number;
+	has_guard_page: boolean;
+	guard_page_address?: string;
 }
 
 export interface StackFrame {
 	instruction_address: string;
+	raw_instruction_address?: string;
 	trust_level: string;
 	module_name?: string;
+	function_name?: string;
+	symbol_source?: 'debuginfo' | 'none';
+	is_signal_frame: boolean;
+	normalized_address?: string;
+	display?: string;
+	is_fault_frame: boolean;
+}
+
+export interface StackFramesPage {
+	total: number;
+	start: number;
+	frames: StackFrame[];
 }
 
 export interface ThreadData {
@@ -90,11 +165,20 @@ export interface ThreadData {
 	priority_class: number;
 	priority: number;
 	teb: string;
+	is_crashing: boolean;
+	synthetic: boolean;
+	exit_code?: number;
+	exit_code_name?: string;
+	creation_rank?: number;
+	wait_reason?: string;
+	stack_pointer_in_bounds: boolean;
 	stack?: StackInfo;
 	context?: StructuredContext;
+	context_suspect: boolean;
 	stack_frames?: StackFrame[];
 	debug?: string;
-	stack_unwinding_method: 'Ok' | 'Fallback' | 'Failed';
+	stack_unwinding_method: 'Ok' | 'OkNoSymbols' | 'Fallback' | 'Failed';
+	unwind_truncated_reason?: string;
 }
 
 // === Module Types ===
@@ -111,25 +195,67 @@ export interface CodeViewInfo {
 	identifier?: string;
 	age?: number;
 	pdb_filename?: string;
+	debug_identifier?: string;
+}
+
+export interface MiscRecordInfo {
+	flags: number;
+	unicode: boolean;
+	debug_path?: string;
 }
 
 export interface ModuleInfo {
 	name: string;
+	name_valid: boolean;
+	normalized_name: string;
 	base_of_image: string;
 	size_of_image: number;
 	checksum: number;
 	time_date_stamp: number;
 	version_info?: VersionInfo;
 	cv_record_info?: CodeViewInfo;
-	misc_record_present: boolean;
+	misc_record?: MiscRecordInfo;
+	is_system?: boolean;
+	system_heuristic?: string;
+	code_crc32?: number;
+}
+
+export interface ModuleStats {
+	with_version: number;
+	with_codeview: number;
+	system: number;
+	app: number;
 }
 
 export interface ModuleData {
 	modules: ModuleInfo[];
 	modules_count: number;
+	module_stats: ModuleStats;
 	debug?: string;
 }
 
+export interface SymbolModule {
+	code_file: string;
+	code_id?: string;
+	debug_file?: string;
+	debug_id?: string;
+}
+
+export interface SymbolMatch {
+	matched: boolean;
+	debug_id?: string;
+	module_name?: string;
+	base_of_image?: string;
+}
+
+// === Address Lookup Types ===
+export interface AddressDescription {
+	module?: { name: string; offset: number };
+	memory_region?: { base: string; protection?: string };
+	symbol?: NearestSymbol;
+	is_stack?: number;
+}
+
 // === Memory Types ===
 export interface MemoryRegion {
 	start_address: string;
@@ -139,6 +265,13 @@ export interface MemoryRegion {
 	has_data: boolean;
 	data_size: number;
 	address_range: string;
+	entropy?: number;
+	stack_owner?: StackOwner;
+}
+
+export interface StackOwner {
+	thread_id: number;
+	thread_name?: string;
 }
 
 export interface MemoryInfoRange {
@@ -152,13 +285,24 @@ export interface MemoryInfoRange {
 	protection_value: number;
 	allocation_protection: string;
 	allocation_protection_value: number;
+	protection_changed: boolean;
+	protection_note?: string;
 	memory_type: string;
 	memory_type_value: number;
 }
 
+export interface MemoryInfoListHeader {
+	size_of_header: number;
+	size_of_entry: number;
+	number_of_entries: number;
+	entry_size_matches: boolean;
+}
+
 export interface MemoryRangeMap {
 	ranges: MemoryInfoRange[];
 	ranges_count: number;
+	list_header?: MemoryInfoListHeader;
+	rwx_regions: string[];
 }
 
 export interface MemoryData {
@@ -168,19 +312,186 @@ export interface MemoryData {
 	has_memory_info_stream: boolean;
 	total_memory_size: number;
 	total_memory_size_formatted: string;
+	total_captured_size: number;
+	total_captured_size_formatted: string;
 	debug?: string;
 }
 
+// === Crash Types ===
+export interface CrashSummary {
+	crash_address?: string;
+	crashing_thread_id: number;
+	crashing_thread_id_consistent: boolean;
+	exception_stream_thread_id?: number;
+	crashing_thread_in_thread_list: boolean;
+	breakpad_requesting_thread_id?: number;
+	crashing_thread_index?: number;
+	crashing_thread_name?: string;
+	crash_in_guard_page: boolean;
+	null_dereference: boolean;
+	null_offset?: number;
+	severity: 'critical' | 'high' | 'medium' | 'low';
+	confidence: number;
+}
+
+export interface CrashLocation {
+	thread_index: number;
+	frame_index: number;
+	module?: string;
+	function?: string;
+}
+
+export interface PriorityInversionCandidate {
+	waiting_thread_id: number;
+	waiting_priority: number;
+	wait_reason: string;
+	holder_thread_id: number;
+	holder_priority: number;
+	lock_address: string;
+}
+
+// === Token Types ===
+export interface TokenInfo {
+	token_size: number;
+	token_id: number;
+	token_handle: string;
+	handle_type?: string;
+	handle_object_name?: string;
+}
+
+// === JavaScript Data Types ===
+export interface JavaScriptInfo {
+	data_size: number;
+	encoding: string;
+	text: string;
+}
+
+// === Process VM Counters Types ===
+export interface MemorySize {
+	bytes: number;
+	formatted: string;
+}
+
+export interface ProcessVmCounters {
+	revision: number;
+	flags?: number;
+	// Revision 2 only has these with the MINIDUMP_PROCESS_VM_COUNTERS flag
+	page_fault_count?: number;
+	peak_working_set_size?: MemorySize;
+	working_set_size?: MemorySize;
+	quota_peak_paged_pool_usage?: MemorySize;
+	quota_paged_pool_usage?: MemorySize;
+	quota_peak_non_paged_pool_usage?: MemorySize;
+	quota_non_paged_pool_usage?: MemorySize;
+	pagefile_usage?: MemorySize;
+	peak_pagefile_usage?: MemorySize;
+	private_usage?: MemorySize;
+	peak_virtual_size?: MemorySize;
+	virtual_size?: MemorySize;
+	private_working_set_size?: MemorySize;
+	shared_commit_usage?: MemorySize;
+	job_shared_commit_usage?: MemorySize;
+	job_private_commit_usage?: MemorySize;
+	job_peak_private_commit_usage?: MemorySize;
+	job_private_commit_limit?: MemorySize;
+	job_total_commit_limit?: MemorySize;
+}
+
+// === Stream Directory Types ===
+export interface DumpHeader {
+	signature: number;
+	version: number;
+	stream_count: number;
+	stream_directory_rva: number;
+	checksum: number;
+	time_date_stamp: number;
+	flags: string;
+}
+
+export interface StreamDirectoryEntry {
+	stream_type: number;
+	rva: number;
+	data_size: number;
+	name?: string;
+	display_name: string;
+}
+
+export interface StreamPresence {
+	name: string;
+	stream_type: number;
+	parsed: boolean;
+}
+
+export interface DumpTime {
+	utc: string;
+	local_time?: string;
+	timezone_name?: string;
+}
+
+export interface ProcessorThreadCounts {
+	processors: number;
+	threads: number;
+}
+
 // === Main Result Type ===
 export interface MinidumpResult {
-	streams_present?: string[];
+	schema_version: number;
+	is_empty?: boolean;
+	header?: DumpHeader;
+	dump_time?: DumpTime;
+	streams_present?: StreamPresence[];
+	stream_directory?: StreamDirectoryEntry[];
+	dump_completeness?: 'micro' | 'mini' | 'full';
 	modules_count?: number;
 	threads_count?: number;
+	processor_thread_counts?: ProcessorThreadCounts;
 	system_info?: SystemInfoData;
+	system_info_inferred?: boolean;
+	misc_info?: MiscInfoData;
 	exception_info?: ExceptionData;
+	crash_summary?: CrashSummary;
+	capture_reason: 'unhandled_exception' | 'manual' | 'assertion' | 'unknown';
+	crash_signature?: string;
+	crash_signature_hash?: string;
+	crash_location?: CrashLocation;
+	priority_inversion_candidates: PriorityInversionCandidate[];
 	threads_data?: ThreadData[];
 	modules_data?: ModuleData;
+	mixed_architecture_modules?: string[];
+	required_symbols: SymbolModule[];
 	memory_data?: MemoryData;
+	token_info?: TokenInfo[];
+	javascript_info?: JavaScriptInfo;
+	process_vm_counters?: ProcessVmCounters;
+	command_line?: string;
+	environment: string[];
+	annotations: Annotation[];
+}
+
+export interface Annotation {
+	source: string;
+	key?: string;
+	value: string;
+}
+
+// === Parse Options ===
+export interface ParseOptions {
+	compute_entropy?: boolean;
+	classify_system_modules?: boolean;
+	include_exception_debug?: boolean;
+	include_exception_context_debug?: boolean;
+	allow_stack_scan?: boolean;
+	size_units?: 'iec' | 'si';
+	symbolicate_crashing_thread_only?: boolean;
+	normalize_addresses?: boolean;
+	frame_display?: boolean;
+	merge_context_frame?: boolean;
+	detect_priority_inversion?: boolean;
+	thread_context_crashing_only?: boolean;
+	thread_debug_crashing_only?: boolean;
+	register_raw_bytes?: boolean;
+	module_code_crc32?: boolean;
+	read_shadow_stack?: boolean;
 }
 
 // === Component Helper Types ===
@@ -0,0 +1,97 @@
+use crate::common::SafeU64;
+use crate::exception::{NearestSymbol, resolve_nearest_symbol};
+use crate::memory::protection_name;
+use crate::modules::ModuleIndex;
+use minidump::{
+    MinidumpMemoryInfoList, MinidumpModuleList, MinidumpSystemInfo, MinidumpThreadList,
+    UnifiedMemoryList,
+};
+use serde::Serialize;
+
+// Everything the dump can say about one address. Each part is None when the address isn't
+// in a module, region, symbol or stack respectively, so an unmapped address is all None.
+#[derive(Serialize)]
+pub struct AddressDescription {
+    pub module: Option<AddressModule>,
+    pub memory_region: Option<AddressRegion>,
+    pub symbol: Option<NearestSymbol>,
+    pub is_stack: Option<u32>, // Id of the thread whose captured stack holds the address
+}
+
+#[derive(Serialize)]
+pub struct AddressModule {
+    pub name: String,
+    pub offset: u64, // address - module base
+}
+
+#[derive(Serialize)]
+pub struct AddressRegion {
+    pub base: SafeU64,
+    // From the memory info stream; None when only the captured memory list covers the address
+    pub protection: Option<String>,
+}
+
+// MINIDUMP_MEMORY_INFO.State of unallocated address space
+const MEM_FREE: u32 = 0x10000;
+
+pub struct AddressSources<'a> {
+    pub system: Option<&'a MinidumpSystemInfo>,
+    pub modules: Option<&'a MinidumpModuleList>,
+    pub threads: Option<&'a MinidumpThreadList<'a>>,
+    pub memory: Option<&'a UnifiedMemoryList<'a>>,
+    pub memory_info: Option<&'a MinidumpMemoryInfoList<'a>>,
+}
+
+pub async fn build_address_description(
+    address: u64,
+    sources: &AddressSources<'_>,
+) -> AddressDescription {
+    let module = sources.modules.and_then(|modules| {
+        let module = ModuleIndex::new(modules).module_for_address(address)?;
+        Some(AddressModule {
+            name: module.name.clone(),
+            offset: address - module.raw.base_of_image,
+        })
+    });
+
+    // The memory info stream also covers memory that wasn't captured, so prefer it
+    let memory_region = sources
+        .memory_info
+        .and_then(|info| info.memory_info_at_address(address))
+        .filter(|info| info.raw.state != MEM_FREE)
+        .map(|info| AddressRegion {
+            base: info.raw.base_address.into(),
+            protection: Some(protection_name(info.raw.protection)),
+        })
+        .or_else(|| {
+            let region = sources.memory?.memory_at_address(address)?;
+            Some(AddressRegion {
+                base: region.base_address().into(),
+                protection: None,
+            })
+        });
+
+    let symbol = match (sources.system, sources.modules) {
+        (Some(system), Some(modules)) => resolve_nearest_symbol(address, system, modules).await,
+        _ => None,
+    };
+
+    let is_stack = sources.threads.and_then(|threads| {
+        threads
+            .threads
+            .iter()
+            .find(|thread| {
+                let start = thread.raw.stack.start_of_memory_range;
+                let size = thread.raw.stack.memory.data_size as u64;
+                start != 0 && (start..start.saturating_add(size)).contains(&address)
+            })
+            .map(|thread| thread.raw.thread_id)
+    });
+
+    AddressDescription {
+        module,
+        memory_region,
+        symbol,
+        is_stack,
+    }
+}
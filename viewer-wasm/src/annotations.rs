@@ -0,0 +1,102 @@
+use crate::modules::normalized_module_name;
+use minidump::{
+    Minidump, MinidumpAnnotation, MinidumpAssertion, MinidumpCrashpadInfo, MinidumpModuleList,
+};
+use serde::Serialize;
+
+// MINIDUMP_STREAM_TYPE::CommentStreamA and CommentStreamW, not parsed by the minidump crate
+const COMMENT_STREAM_A_TYPE: u32 = 10;
+const COMMENT_STREAM_W_TYPE: u32 = 11;
+
+#[derive(Serialize)]
+pub struct Annotation {
+    pub source: String,      // Stream it came from, e.g. "CrashpadInfoStream"
+    pub key: Option<String>, // None for comments
+    pub value: String,
+}
+
+// The dump's free-form text in one list: Crashpad annotations (process-wide, then each
+// module's: list annotations keyed by the module, the others "module!key"), comment streams,
+// and the assertion info stream. Crashpad annotation objects that aren't strings are left out.
+pub fn collect_annotations(
+    dump: &Minidump<'_, &[u8]>,
+    modules: Option<&MinidumpModuleList>,
+) -> Vec<Annotation> {
+    let mut annotations = Vec::new();
+    let mut push = |source: &str, key: Option<String>, value: String| {
+        if !value.is_empty() {
+            annotations.push(Annotation {
+                source: source.to_string(),
+                key,
+                value,
+            });
+        }
+    };
+
+    if let Ok(crashpad) = dump.get_stream::<MinidumpCrashpadInfo>() {
+        const SOURCE: &str = "CrashpadInfoStream";
+        for (key, value) in &crashpad.simple_annotations {
+            push(SOURCE, Some(key.clone()), value.clone());
+        }
+        for module_info in &crashpad.module_list {
+            let module = modules
+                .and_then(|modules| modules.iter().nth(module_info.module_index))
+                .map_or_else(
+                    || format!("module {}", module_info.module_index),
+                    |module| normalized_module_name(&module.name),
+                );
+            for value in &module_info.list_annotations {
+                push(SOURCE, Some(module.clone()), value.clone());
+            }
+            for (key, value) in &module_info.simple_annotations {
+                push(SOURCE, Some(format!("{}!{}", module, key)), value.clone());
+            }
+            for (key, annotation) in &module_info.annotation_objects {
+                if let MinidumpAnnotation::String(value) = annotation {
+                    push(SOURCE, Some(format!("{}!{}", module, key)), value.clone());
+                }
+            }
+        }
+    }
+
+    if let Ok(bytes) = dump.get_raw_stream(COMMENT_STREAM_A_TYPE) {
+        let text = String::from_utf8_lossy(bytes);
+        push(
+            "CommentStreamA",
+            None,
+            text.trim_end_matches('\0').to_string(),
+        );
+    }
+    if let Ok(bytes) = dump.get_raw_stream(COMMENT_STREAM_W_TYPE) {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        let text = String::from_utf16_lossy(&units);
+        push(
+            "CommentStreamW",
+            None,
+            text.trim_end_matches('\0').to_string(),
+        );
+    }
+
+    if let Ok(assertion) = dump.get_stream::<MinidumpAssertion>() {
+        const SOURCE: &str = "AssertionInfoStream";
+        let fields = [
+            ("expression", assertion.expression()),
+            ("function", assertion.function()),
+            ("file", assertion.file()),
+            (
+                "line",
+                (assertion.raw.line != 0).then(|| assertion.raw.line.to_string()),
+            ),
+        ];
+        for (key, value) in fields {
+            if let Some(value) = value {
+                push(SOURCE, Some(key.to_string()), value);
+            }
+        }
+    }
+
+    annotations
+}
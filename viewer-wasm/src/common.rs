@@ -55,3 +55,19 @@ impl<T: Debug> DebugSerializable for T {
 pub fn debug_output<T: Debug>(item: &T) -> Option<String> {
     Some(item.debug_string())
 }
+
+/// Read a little-endian u32 at `offset`, returning None if the buffer is too short
+pub fn read_u32_le(bytes: &[u8], offset: usize) -> Option<u32> {
+    let end = offset.checked_add(4)?;
+    bytes
+        .get(offset..end)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// Read a little-endian u64 at `offset`, returning None if the buffer is too short
+pub fn read_u64_le(bytes: &[u8], offset: usize) -> Option<u64> {
+    let end = offset.checked_add(8)?;
+    bytes
+        .get(offset..end)
+        .map(|b| u64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+}
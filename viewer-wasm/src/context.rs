@@ -1,5 +1,6 @@
-use crate::common::SafeU64;
-use minidump::{MinidumpContext, MinidumpRawContext};
+use crate::common::{SafeU64, read_u64_le};
+use minidump::system_info::Cpu;
+use minidump::{MinidumpContext, MinidumpMiscInfo, MinidumpRawContext};
 use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::OnceLock;
@@ -10,6 +11,30 @@ pub struct RegisterValue {
     pub value: SafeU64,
     pub category: String,
     pub valid: bool,
+    pub raw_bytes: Option<Vec<u8>>, // On-disk little-endian bytes, with `register_raw_bytes`
+}
+
+#[derive(Serialize)]
+pub struct VectorRegisterValue {
+    pub name: String,
+    pub value: String, // Full-width hex value, e.g. 128-bit NEON registers
+    pub category: String,
+    pub valid: bool,
+    pub raw_bytes: Option<Vec<u8>>, // On-disk little-endian bytes, with `register_raw_bytes`
+}
+
+#[derive(Serialize)]
+pub struct FpuState {
+    // x87 FLOATING_SAVE_AREA of an x86 context
+    pub control_word: u32,
+    pub status_word: u32,
+    pub tag_word: u32,
+    pub instruction_pointer: SafeU64, // Last FPU instruction (error_offset)
+    pub instruction_selector: u32,
+    pub data_pointer: SafeU64,
+    pub data_selector: u32,
+    pub st: Vec<VectorRegisterValue>, // st0-st7, 80-bit values as hex
+    pub valid: bool,
 }
 
 #[derive(Serialize)]
@@ -20,17 +45,44 @@ pub struct StructuredContext {
     pub flags: Vec<RegisterValue>,
     pub debug: Vec<RegisterValue>,
     pub other: Vec<RegisterValue>,
+    pub vector: Vec<VectorRegisterValue>,
+    pub fpu: Option<FpuState>, // x86 only
     pub architecture: String,
 }
 
+// CONTEXT_ARM64 | CONTEXT_FLOATING_POINT
+const CONTEXT_ARM64_FLOATING_POINT: u32 = 0x0040_0004;
+
+// CONTEXT_X86 | CONTEXT_FLOATING_POINT
+const CONTEXT_X86_FLOATING_POINT: u32 = 0x0001_0008;
+
 // Helper function to parse context into structured register data
-pub fn parse_context_registers(context: &MinidumpContext) -> StructuredContext {
+// Whether the context record is the one the SystemInfo CPU would produce. A mismatch means
+// one of the two is corrupt; registers are still labeled from the context's own layout, see
+// parse_context_registers.
+pub fn context_matches_cpu(context: &MinidumpContext, cpu: Cpu) -> bool {
+    match &context.raw {
+        MinidumpRawContext::X86(_) => cpu == Cpu::X86,
+        MinidumpRawContext::Amd64(_) => cpu == Cpu::X86_64,
+        MinidumpRawContext::Arm(_) => cpu == Cpu::Arm,
+        MinidumpRawContext::Arm64(_) | MinidumpRawContext::OldArm64(_) => cpu == Cpu::Arm64,
+        MinidumpRawContext::Ppc(_) => cpu == Cpu::Ppc,
+        MinidumpRawContext::Ppc64(_) => cpu == Cpu::Ppc64,
+        MinidumpRawContext::Sparc(_) => cpu == Cpu::Sparc,
+        MinidumpRawContext::Mips(_) => matches!(cpu, Cpu::Mips | Cpu::Mips64),
+    }
+}
+
+// `raw_bytes` also fills in each register's little-endian bytes as stored in the context
+// record, at the register's real width (u32 on 32-bit CPUs, u128 for NEON, 80 bits for x87).
+pub fn parse_context_registers(context: &MinidumpContext, raw_bytes: bool) -> StructuredContext {
     let mut general_purpose = Vec::new();
     let mut instruction_pointer = Vec::new();
     let mut segment = Vec::new();
     let mut flags = Vec::new();
     let mut debug = Vec::new();
     let mut other = Vec::new();
+    let mut vector = Vec::new();
 
     // Get architecture from the raw context type
     let architecture = match &context.raw {
@@ -46,6 +98,9 @@ pub fn parse_context_registers(context: &MinidumpContext) -> StructuredContext {
     let valid_reg_names: std::collections::HashSet<&str> =
         context.valid_registers().map(|(name, _)| name).collect();
 
+    // registers() widens every value to u64; on disk they are all register_size() bytes
+    let register_size = context.register_size();
+
     // Get all registers from the context (direct call, no cpu_context())
     for (name, value) in context.registers() {
         let reg_name = name.to_string();
@@ -57,6 +112,7 @@ pub fn parse_context_registers(context: &MinidumpContext) -> StructuredContext {
             value: value.into(),
             valid,
             category: category.clone(),
+            raw_bytes: raw_bytes.then(|| value.to_le_bytes()[..register_size].to_vec()),
         };
 
         // Push to appropriate category vector
@@ -70,6 +126,38 @@ pub fn parse_context_registers(context: &MinidumpContext) -> StructuredContext {
         }
     }
 
+    // ARM64 floating point state isn't part of registers(), so decode it from the raw context
+    if let MinidumpRawContext::Arm64(raw) = &context.raw {
+        let fp_valid =
+            raw.context_flags & CONTEXT_ARM64_FLOATING_POINT == CONTEXT_ARM64_FLOATING_POINT;
+
+        for (name, value) in [("fpsr", raw.fpsr), ("fpcr", raw.fpcr)] {
+            flags.push(RegisterValue {
+                name: name.to_string(),
+                value: (value as u64).into(),
+                category: categorize_register(name),
+                valid: fp_valid,
+                raw_bytes: raw_bytes.then(|| value.to_le_bytes().to_vec()),
+            });
+        }
+
+        for (index, value) in raw.float_regs.iter().enumerate() {
+            vector.push(VectorRegisterValue {
+                name: format!("v{}", index),
+                value: format!("0x{:032x}", value),
+                category: "vector".to_string(),
+                valid: fp_valid,
+                raw_bytes: raw_bytes.then(|| value.to_le_bytes().to_vec()),
+            });
+        }
+    }
+
+    // x87 state lives in FloatingSave, which registers() doesn't cover either
+    let fpu = match &context.raw {
+        MinidumpRawContext::X86(raw) => Some(parse_x87_state(raw, raw_bytes)),
+        _ => None,
+    };
+
     StructuredContext {
         general_purpose,
         instruction_pointer,
@@ -77,10 +165,107 @@ pub fn parse_context_registers(context: &MinidumpContext) -> StructuredContext {
         flags,
         debug,
         other,
+        vector,
+        fpu,
         architecture,
     }
 }
 
+fn parse_x87_state(raw: &minidump::format::CONTEXT_X86, raw_bytes: bool) -> FpuState {
+    let valid = raw.context_flags & CONTEXT_X86_FLOATING_POINT == CONTEXT_X86_FLOATING_POINT;
+    let save = &raw.float_save;
+
+    // register_area holds st0-st7 as consecutive little-endian 80-bit values
+    let st = save
+        .register_area
+        .chunks_exact(10)
+        .enumerate()
+        .map(|(index, bytes)| VectorRegisterValue {
+            name: format!("st{}", index),
+            value: bytes.iter().rev().fold(String::from("0x"), |mut hex, b| {
+                hex.push_str(&format!("{:02x}", b));
+                hex
+            }),
+            category: "fpu".to_string(),
+            valid,
+            raw_bytes: raw_bytes.then(|| bytes.to_vec()),
+        })
+        .collect();
+
+    FpuState {
+        control_word: save.control_word,
+        status_word: save.status_word,
+        tag_word: save.tag_word,
+        instruction_pointer: (save.error_offset as u64).into(),
+        instruction_selector: save.error_selector,
+        data_pointer: (save.data_offset as u64).into(),
+        data_selector: save.data_selector,
+        st,
+        valid,
+    }
+}
+
+// CONTEXT_AMD64 / CONTEXT_X86 with the CONTEXT_XSTATE bit: extended (XSAVE) state is present
+const CONTEXT_AMD64_XSTATE: u32 = 0x0010_0040;
+const CONTEXT_X86_XSTATE: u32 = 0x0001_0040;
+
+// XSTATE_FEATURE offsets are relative to the XSAVE legacy area, which starts this far into
+// CONTEXT_AMD64 (see minidump's XSTATE_CONFIG_FEATURE_MSC_INFO docs)
+const AMD64_XSAVE_AREA_OFFSET: usize = 256;
+
+// XSAVE feature index of the user-mode CET state: IA32_U_CET followed by IA32_PL3_SSP
+const XSTATE_CET_U: usize = 11;
+
+// Recognize extended context state (XSTATE) and surface what we can decode from it in
+// `other`: the enabled XSAVE feature mask, and the CET shadow stack pointer. XSTATE layout
+// comes from MiscInfo 5; without it the flag is all we can report.
+pub fn parse_extended_context(
+    structured: &mut StructuredContext,
+    context: &MinidumpContext,
+    context_bytes: &[u8],
+    misc_info: Option<&MinidumpMiscInfo>,
+    raw_bytes: bool,
+) {
+    let (context_flags, xstate_flag) = match &context.raw {
+        MinidumpRawContext::Amd64(raw) => (raw.context_flags, CONTEXT_AMD64_XSTATE),
+        MinidumpRawContext::X86(raw) => (raw.context_flags, CONTEXT_X86_XSTATE),
+        _ => return,
+    };
+    if context_flags & xstate_flag != xstate_flag {
+        return;
+    }
+
+    let mut push = |name: &str, value: u64, valid: bool| {
+        structured.other.push(RegisterValue {
+            name: name.to_string(),
+            value: value.into(),
+            category: "other".to_string(),
+            valid,
+            raw_bytes: raw_bytes.then(|| value.to_le_bytes().to_vec()),
+        });
+    };
+
+    let xstate = misc_info.and_then(|m| m.raw.xstate_data());
+    push(
+        "xstate_features",
+        xstate.map_or(0, |x| x.enabled_features),
+        xstate.is_some(),
+    );
+
+    // Feature offsets are only documented for the AMD64 layout
+    let Some(xstate) = xstate else { return };
+    if !matches!(context.raw, MinidumpRawContext::Amd64(_)) {
+        return;
+    }
+    if let Some((_, cet)) = xstate.iter().find(|&(index, _)| index == XSTATE_CET_U) {
+        let offset = AMD64_XSAVE_AREA_OFFSET + cet.offset as usize;
+        let u_cet = read_u64_le(context_bytes, offset);
+        let ssp = read_u64_le(context_bytes, offset + 8);
+        push("u_cet", u_cet.unwrap_or(0), u_cet.is_some());
+        push("ssp", ssp.unwrap_or(0), ssp.is_some());
+    }
+}
+
 // Optimized register categorization using static lookup table
 static REGISTER_CATEGORIES: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
 
@@ -107,7 +292,7 @@ fn get_register_categories() -> &'static HashMap<&'static str, &'static str> {
         }
 
         // Flags registers
-        for reg in &["eflags", "rflags", "context_flags", "cpsr"] {
+        for reg in &["eflags", "rflags", "context_flags", "cpsr", "fpsr", "fpcr"] {
             map.insert(*reg, "flags");
         }
 
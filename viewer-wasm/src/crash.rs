@@ -0,0 +1,399 @@
+use crate::common::SafeU64;
+use crate::exception::{
+    EXCEPTION_ACCESS_VIOLATION, ExceptionData, STATUS_BREAKPOINT, STATUS_HEAP_CORRUPTION,
+};
+use crate::threads::{StackFrame, ThreadData};
+use minidump::format::MemoryProtection;
+use minidump::{MinidumpBreakpadInfo, MinidumpMemoryInfoList};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct CrashSummary {
+    pub crash_address: Option<SafeU64>,
+    pub crashing_thread_id: u32, // See `crashing_thread_id()`
+    // The exception stream's thread_id is backed by the rest of the dump: it names a thread of
+    // the thread list (when there is one), and the BreakpadInfo requesting thread (when
+    // present) is the same one. When they disagree (malformed dumps) the stream's value still
+    // wins and the other sources are reported alongside it.
+    pub crashing_thread_id_consistent: bool,
+    pub exception_stream_thread_id: Option<u32>,
+    pub crashing_thread_in_thread_list: bool,
+    pub breakpad_requesting_thread_id: Option<u32>,
+    // The crashing thread in `Overview.threads_data`; None when the dump has no thread list
+    // or the exception names a thread that isn't in it
+    pub crashing_thread_index: Option<usize>,
+    pub crashing_thread_name: Option<String>,
+    // The crash address lies in a PAGE_GUARD region (stack guard page or a manual guard);
+    // false when the dump has no memory info stream to check against
+    pub crash_in_guard_page: bool,
+    // The faulting address is in the zero page, i.e. a null pointer was dereferenced.
+    // null_offset is the address itself: the probable field offset into the null struct.
+    pub null_dereference: bool,
+    pub null_offset: Option<u64>,
+    // Rough triage ranking, not an exploitability verdict; see crash_severity and
+    // crash_confidence for the rubric
+    pub severity: String, // "critical", "high", "medium" or "low"
+    pub confidence: f32,  // 0.0 - 1.0, how far the analysis behind the summary can be trusted
+}
+
+#[derive(Serialize)]
+pub struct CrashLocation {
+    pub thread_index: usize, // Index into `Overview.threads_data`
+    pub frame_index: usize,  // Index into that thread's `stack_frames`
+    pub module: Option<String>,
+    pub function: Option<String>,
+}
+
+// Locate the crash for the UI: the crashing thread and its fault frame (see
+// `StackFrame.is_fault_frame`). Without one, the frame below its signal trampoline, or else
+// its first unwound frame that is neither the context frame nor a scanned guess. Falls back
+// to the top frame when no such frame exists, and to None when the thread or its frames are
+// missing.
+pub fn build_crash_location(
+    threads: &[ThreadData],
+    crashing_thread_id: u32,
+) -> Option<CrashLocation> {
+    let thread_index = threads
+        .iter()
+        .position(|t| t.thread_id == crashing_thread_id)?;
+    let frames = threads[thread_index].stack_frames.as_ref()?;
+
+    // In a signal-delivered crash the frames above the trampoline are the handler; the fault
+    // itself is the frame the trampoline returns to
+    let below_trampoline = frames
+        .iter()
+        .rposition(|f| f.is_signal_frame)
+        .map(|i| i + 1)
+        .filter(|&i| i < frames.len());
+    let frame_index = frames
+        .iter()
+        .position(|f| f.is_fault_frame)
+        .or(below_trampoline)
+        .or_else(|| {
+            frames
+                .iter()
+                .position(|f| f.trust_level != "context" && f.trust_level != "scan")
+        })
+        .unwrap_or(0);
+    let frame = frames.get(frame_index)?;
+
+    Some(CrashLocation {
+        thread_index,
+        frame_index,
+        module: frame.module_name.clone(),
+        function: frame.function_name.clone(),
+    })
+}
+
+// The thread that crashed, used wherever a single crashing thread is needed (summary,
+// signature, `ThreadData.is_crashing`): the MINIDUMP_EXCEPTION_STREAM thread_id as written
+// by the dump writer, falling back to get_crashing_thread_id()
+pub fn crashing_thread_id(exception: &ExceptionData) -> u32 {
+    exception
+        .raw
+        .as_ref()
+        .map_or(exception.thread_id, |raw| raw.thread_id)
+}
+
+// Cross-reference the exception with the rest of the dump
+pub fn build_crash_summary(
+    exception: &ExceptionData,
+    threads: &[ThreadData],
+    memory_info: Option<&MinidumpMemoryInfoList>,
+    breakpad_info: Option<&MinidumpBreakpadInfo>,
+) -> CrashSummary {
+    let crashing_thread_id = crashing_thread_id(exception);
+    let exception_stream_thread_id = exception.raw.as_ref().map(|raw| raw.thread_id);
+    let crashing_thread_index = threads
+        .iter()
+        .position(|t| t.thread_id == crashing_thread_id);
+    // A crashing thread the list lacks is synthesized, so look only at the dump's own threads
+    let has_thread_list = threads.iter().any(|t| !t.synthetic);
+    let crashing_thread_in_thread_list =
+        crashing_thread_index.is_some_and(|i| !threads[i].synthetic);
+    let breakpad_requesting_thread_id = breakpad_info.and_then(|info| info.requesting_thread_id);
+    let crashing_thread_id_consistent = (!has_thread_list || crashing_thread_in_thread_list)
+        && breakpad_requesting_thread_id.is_none_or(|id| id == crashing_thread_id);
+    let crashing_thread_name = crashing_thread_index.and_then(|i| threads[i].name.clone());
+
+    let crash_address = exception.crash_address.as_ref().map(SafeU64::raw_value);
+
+    let crash_in_guard_page = match (crash_address, memory_info) {
+        (Some(address), Some(memory_info)) => memory_info
+            .memory_info_at_address(address)
+            .is_some_and(|info| info.protection.contains(MemoryProtection::PAGE_GUARD)),
+        _ => false,
+    };
+
+    let null_offset = fault_target_address(exception)
+        .or(crash_address)
+        .filter(|&address| address < NULL_PAGE_SIZE);
+
+    let (severity, class_recognized) = crash_severity(exception, null_offset.is_some());
+    let confidence = crash_confidence(
+        class_recognized,
+        crashing_thread_index.and_then(|i| threads[i].stack_frames.as_deref()),
+    );
+
+    CrashSummary {
+        crash_address: crash_address.map(Into::into),
+        crashing_thread_id,
+        crashing_thread_id_consistent,
+        exception_stream_thread_id,
+        crashing_thread_in_thread_list,
+        breakpad_requesting_thread_id,
+        crashing_thread_index,
+        crashing_thread_name,
+        crash_in_guard_page,
+        null_dereference: null_offset.is_some(),
+        null_offset,
+        severity: severity.to_string(),
+        confidence,
+    }
+}
+
+const STATUS_ILLEGAL_INSTRUCTION: u32 = 0xC000_001D;
+const STATUS_INTEGER_DIVIDE_BY_ZERO: u32 = 0xC000_0094;
+const STATUS_PRIVILEGED_INSTRUCTION: u32 = 0xC000_0096;
+const STATUS_STACK_OVERFLOW: u32 = 0xC000_00FD;
+const STATUS_STACK_BUFFER_OVERRUN: u32 = 0xC000_0409;
+
+// EXCEPTION_ACCESS_VIOLATION ExceptionInformation[0]
+const ACCESS_VIOLATION_READ: u64 = 0;
+
+// Severity by crash class, first match wins:
+// - "critical": likely memory corruption: a write or execute access violation outside the
+//   null page, STATUS_HEAP_CORRUPTION or STATUS_STACK_BUFFER_OVERRUN
+// - "high": a read access violation outside the null page, a null-page write or execute,
+//   or a SIGSEGV/SIGBUS outside the null page
+// - "medium": a null-page read or SIGSEGV/SIGBUS, stack overflow, illegal or privileged
+//   instruction, integer divide by zero
+// - "low": anything else, e.g. breakpoints, assertions, aborts and C++ exceptions
+// Also returns whether the class was one of the recognized ones above.
+fn crash_severity(exception: &ExceptionData, null_dereference: bool) -> (&'static str, bool) {
+    let record = exception.raw.as_ref().map(|raw| &raw.exception_record);
+    let code = record.map(|record| record.exception_code);
+    let posix_memory_fault = exception
+        .crash_reason
+        .as_deref()
+        .is_some_and(|reason| reason.starts_with("SIGSEGV") || reason.starts_with("SIGBUS"));
+
+    let severity = match code {
+        Some(EXCEPTION_ACCESS_VIOLATION) => {
+            let read = record
+                .and_then(|record| record.exception_information.first())
+                .is_none_or(|access| access.raw_value() == ACCESS_VIOLATION_READ);
+            match (null_dereference, read) {
+                (false, false) => "critical",
+                (false, true) | (true, false) => "high",
+                (true, true) => "medium",
+            }
+        }
+        Some(STATUS_HEAP_CORRUPTION | STATUS_STACK_BUFFER_OVERRUN) => "critical",
+        Some(
+            STATUS_STACK_OVERFLOW
+            | STATUS_ILLEGAL_INSTRUCTION
+            | STATUS_PRIVILEGED_INSTRUCTION
+            | STATUS_INTEGER_DIVIDE_BY_ZERO,
+        ) => "medium",
+        _ if posix_memory_fault && null_dereference => "medium",
+        _ if posix_memory_fault => "high",
+        _ => return ("low", false),
+    };
+    (severity, true)
+}
+
+// Confidence is the sum of:
+// - 0.4 when the crash class is one crash_severity recognizes, else 0.2
+// - 0.3 x the share of the crashing thread's frames that have a function name
+// - 0.3 x the share of its frames recovered from the context or CFI, with frame-pointer frames
+//   counting half and scanned frames nothing
+// Without the crashing thread's frames only the first part counts.
+fn crash_confidence(class_recognized: bool, frames: Option<&[StackFrame]>) -> f32 {
+    let class = if class_recognized { 0.4 } else { 0.2 };
+    let Some(frames) = frames.filter(|frames| !frames.is_empty()) else {
+        return class;
+    };
+
+    let count = frames.len() as f32;
+    let symbolicated = frames
+        .iter()
+        .filter(|frame| frame.function_name.is_some())
+        .count() as f32;
+    let trust: f32 = frames
+        .iter()
+        .map(|frame| match frame.trust_level.as_str() {
+            "context" | "cfi" => 1.0,
+            "frame_pointer" => 0.5,
+            _ => 0.0,
+        })
+        .sum();
+    class + 0.3 * symbolicated / count + 0.3 * trust / count
+}
+
+// Addresses below this are treated as a null pointer plus a small offset
+const NULL_PAGE_SIZE: u64 = 0x1000;
+
+// The inaccessible address of an access violation: ExceptionInformation[1], next to the
+// read/write/execute flag in [0]
+fn fault_target_address(exception: &ExceptionData) -> Option<u64> {
+    let record = &exception.raw.as_ref()?.exception_record;
+    if record.exception_code != EXCEPTION_ACCESS_VIOLATION {
+        return None;
+    }
+    record.exception_information.get(1).map(SafeU64::raw_value)
+}
+
+// Breakpad's MD_ASSERTION_INFO_STREAM, written when a CRT assertion or invalid parameter
+// handler requested the dump
+pub const ASSERTION_INFO_STREAM_TYPE: u32 = 0x4767_0002;
+
+const STATUS_SINGLE_STEP: u32 = 0x8000_0004;
+const STATUS_ASSERTION_FAILURE: u32 = 0xC000_0420;
+// Breakpad's MD_EXCEPTION_CODE_... for a dump requested without a crash ("DUMP_REQUESTED")
+const DUMP_REQUESTED: u32 = 0xFFFF_FFFF;
+
+// Best-effort guess at why the dump was written, in this order:
+// - "assertion": an assertion info stream is present, or the code is STATUS_ASSERTION_FAILURE
+// - "manual": there is no exception stream (the dump was requested, e.g. by a debugger or
+//   Task Manager), or the exception is a breakpoint, single step, or Breakpad's DUMP_REQUESTED
+// - "unhandled_exception": any other exception
+// "unknown" is reserved for exception streams without an exception record to go by.
+pub fn infer_capture_reason(
+    exception: Option<&ExceptionData>,
+    has_assertion_stream: bool,
+) -> &'static str {
+    if has_assertion_stream {
+        return "assertion";
+    }
+    let Some(exception) = exception else {
+        return "manual";
+    };
+    match exception
+        .raw
+        .as_ref()
+        .map(|raw| raw.exception_record.exception_code)
+    {
+        Some(STATUS_ASSERTION_FAILURE) => "assertion",
+        Some(STATUS_BREAKPOINT | STATUS_SINGLE_STEP | DUMP_REQUESTED) => "manual",
+        Some(_) => "unhandled_exception",
+        None => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        DumpBuilder, Exception, PLATFORM_WIN32_NT, PROCESSOR_ARCHITECTURE_AMD64, SystemInfo,
+        Thread, amd64_context, block_on,
+    };
+    use crate::{Overview, ParseOptions, parse_overview};
+
+    const CRASH_IP: u64 = 0x1_4000_1234;
+
+    fn windows_amd64() -> DumpBuilder {
+        DumpBuilder::new().system_info(SystemInfo::new(
+            PROCESSOR_ARCHITECTURE_AMD64,
+            PLATFORM_WIN32_NT,
+        ))
+    }
+
+    fn access_violation(thread_id: u32, address: u64) -> Exception {
+        let context = amd64_context(CRASH_IP, 0x10_0100, 0x10_0200);
+        let mut exception =
+            Exception::new(thread_id, EXCEPTION_ACCESS_VIOLATION, CRASH_IP, context);
+        exception.parameters = vec![ACCESS_VIOLATION_READ, address];
+        exception
+    }
+
+    fn overview(builder: DumpBuilder) -> Overview {
+        block_on(parse_overview(&builder.build(), &ParseOptions::default())).unwrap()
+    }
+
+    #[test]
+    fn summarizes_an_exception_without_a_thread_list() {
+        let overview = overview(windows_amd64().exception(access_violation(0x10, 0x18)));
+        assert!(overview.threads_data.is_none());
+
+        let summary = overview.crash_summary.unwrap();
+        assert_eq!(summary.crashing_thread_id, 0x10);
+        assert_eq!(summary.crashing_thread_index, None);
+        assert_eq!(summary.crashing_thread_name, None);
+        assert_eq!(summary.crash_address.unwrap().raw_value(), 0x18);
+        assert!(summary.null_dereference);
+        assert_eq!(summary.null_offset, Some(0x18));
+    }
+
+    const BREAKPAD_INFO_STREAM: u32 = 0x47670001;
+
+    // MINIDUMP_BREAKPAD_INFO naming the thread that requested the dump
+    fn breakpad_info(requesting_thread_id: u32) -> Vec<u8> {
+        const REQUESTING_THREAD_ID_VALID: u32 = 2;
+        [REQUESTING_THREAD_ID_VALID, 0, requesting_thread_id]
+            .iter()
+            .flat_map(|field| field.to_le_bytes())
+            .collect()
+    }
+
+    // Threads 0x10 and 0x20 of which `exception_thread_id` crashed
+    fn two_thread_crash(exception_thread_id: u32, breakpad_info: Option<Vec<u8>>) -> Overview {
+        let context = amd64_context(CRASH_IP, 0x10_0100, 0x10_0200);
+        let mut builder = windows_amd64()
+            .thread(Thread::new(
+                0x10,
+                context.clone(),
+                0x10_0000,
+                vec![0; 0x400],
+            ))
+            .thread(Thread::new(0x20, context, 0x20_0000, vec![0; 0x400]))
+            .exception(access_violation(exception_thread_id, 0x5000));
+        if let Some(breakpad_info) = breakpad_info {
+            builder = builder.stream(BREAKPAD_INFO_STREAM, breakpad_info);
+        }
+        overview(builder)
+    }
+
+    #[test]
+    fn marks_the_crashing_thread() {
+        let overview = two_thread_crash(0x20, Some(breakpad_info(0x20)));
+        let threads = overview.threads_data.unwrap();
+        let crashing: Vec<bool> = threads.iter().map(|t| t.is_crashing).collect();
+        assert_eq!(crashing, [false, true]);
+
+        let summary = overview.crash_summary.unwrap();
+        assert_eq!(summary.crashing_thread_id, 0x20);
+        assert_eq!(summary.crashing_thread_index, Some(1));
+        assert!(summary.crashing_thread_id_consistent);
+        assert_eq!(summary.exception_stream_thread_id, Some(0x20));
+        assert!(summary.crashing_thread_in_thread_list);
+        assert_eq!(summary.breakpad_requesting_thread_id, Some(0x20));
+    }
+
+    #[test]
+    fn prefers_the_exception_stream_thread_id_when_breakpad_info_disagrees() {
+        let overview = two_thread_crash(0x20, Some(breakpad_info(0x10)));
+        let crashing: Vec<bool> = overview
+            .threads_data
+            .unwrap()
+            .iter()
+            .map(|t| t.is_crashing)
+            .collect();
+        assert_eq!(crashing, [false, true]);
+
+        let summary = overview.crash_summary.unwrap();
+        assert_eq!(summary.crashing_thread_id, 0x20);
+        assert!(!summary.crashing_thread_id_consistent);
+        assert!(summary.crashing_thread_in_thread_list);
+        assert_eq!(summary.breakpad_requesting_thread_id, Some(0x10));
+    }
+
+    #[test]
+    fn flags_a_crashing_thread_missing_from_the_thread_list() {
+        let summary = two_thread_crash(0x30, None).crash_summary.unwrap();
+        assert_eq!(summary.crashing_thread_id, 0x30);
+        assert!(!summary.crashing_thread_id_consistent);
+        assert!(!summary.crashing_thread_in_thread_list);
+        assert_eq!(summary.breakpad_requesting_thread_id, None);
+    }
+}
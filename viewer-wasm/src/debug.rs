@@ -1,5 +1,9 @@
+use crate::common::SafeU64;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use minidump::{MinidumpModuleList, Module};
 use serde::Serialize;
-use symbolic::debuginfo::Object;
+use std::io::Read;
+use symbolic::debuginfo::Archive;
 
 #[derive(Serialize)]
 pub struct Meta {
@@ -7,17 +11,237 @@ pub struct Meta {
     pub arch: Option<String>,
     pub debug_id: Option<String>,
     pub code_id: Option<String>,
+
+    // What the file can actually be used for
+    pub has_symbols: bool,     // Symbol table (e.g. PDB publics, ELF symtab)
+    pub has_debug_info: bool,  // Line tables / source information
+    pub has_unwind_info: bool, // CFI for stack unwinding
+    pub symbol_count: Option<usize>, // None when there is no symbol table
 }
 
+// Upper bound on a decompressed file, so a small malicious input can't exhaust memory
+const MAX_DECOMPRESSED_BYTES: u64 = 512 * 1024 * 1024;
+
 // Optional: prove `symbolic` compiles on Wasm and let users drop a PDB/ELF/Mach-O/Breakpad file
-// to show basic metadata. This doesn't run during minidump parsing.
-pub fn parse_dif_metadata(bytes: &[u8]) -> Result<Meta, String> {
-    let obj = Object::parse(bytes).map_err(|e| format!("DIF parse: {e}"))?;
-
-    Ok(Meta {
-        kind: format!("{:?}", obj.kind()),
-        arch: Some(obj.arch().to_string()),
-        debug_id: Some(obj.debug_id().to_string()),
-        code_id: obj.code_id().map(|c| c.to_string()),
+// to show basic metadata. This doesn't run during minidump parsing. Gzip/zlib-wrapped files
+// are decompressed first, and fat Mach-O files yield one entry per contained object.
+pub fn parse_dif_metadata(bytes: &[u8]) -> Result<Vec<Meta>, String> {
+    let decompressed = decompress(bytes)?;
+    let bytes = decompressed.as_deref().unwrap_or(bytes);
+
+    let archive = Archive::parse(bytes).map_err(|e| format!("DIF parse: {e}"))?;
+    archive
+        .objects()
+        .map(|obj| {
+            let obj = obj.map_err(|e| format!("DIF parse: {e}"))?;
+            Ok(Meta {
+                kind: format!("{:?}", obj.kind()),
+                arch: Some(obj.arch().to_string()),
+                debug_id: Some(obj.debug_id().to_string()),
+                code_id: obj.code_id().map(|c| c.to_string()),
+                has_symbols: obj.has_symbols(),
+                has_debug_info: obj.has_debug_info(),
+                has_unwind_info: obj.has_unwind_info(),
+                symbol_count: obj.has_symbols().then(|| obj.symbols().count()),
+            })
+        })
+        .collect()
+}
+
+#[derive(Serialize)]
+pub struct SymbolMatch {
+    pub matched: bool,
+    pub debug_id: Option<String>, // Breakpad form; the matching object's, or the file's first
+    pub module_name: Option<String>,
+    pub base_of_image: Option<SafeU64>,
+}
+
+// Find the dump module a dropped symbol file belongs to, comparing Breakpad debug
+// identifiers (GUID/build id plus age). Each object of a fat file is tried.
+pub fn match_symbol_to_modules(
+    symbol: &[u8],
+    modules: &MinidumpModuleList,
+) -> Result<SymbolMatch, String> {
+    let decompressed = decompress(symbol)?;
+    let symbol = decompressed.as_deref().unwrap_or(symbol);
+    let archive = Archive::parse(symbol).map_err(|e| format!("DIF parse: {e}"))?;
+
+    let mut first_debug_id = None;
+    for obj in archive.objects() {
+        let obj = obj.map_err(|e| format!("DIF parse: {e}"))?;
+        let debug_id = obj.debug_id().breakpad().to_string();
+        let module = modules.iter().find(|module| {
+            module
+                .debug_identifier()
+                .is_some_and(|id| id.breakpad().to_string() == debug_id)
+        });
+        if let Some(module) = module {
+            return Ok(SymbolMatch {
+                matched: true,
+                debug_id: Some(debug_id),
+                module_name: Some(module.name.clone()),
+                base_of_image: Some(module.raw.base_of_image.into()),
+            });
+        }
+        first_debug_id.get_or_insert(debug_id);
+    }
+
+    Ok(SymbolMatch {
+        matched: false,
+        debug_id: first_debug_id,
+        module_name: None,
+        base_of_image: None,
     })
 }
+
+// Unwrap a gzip or zlib stream. Returns None for uncompressed input and an error for
+// compression formats we recognize but can't decode.
+fn decompress(bytes: &[u8]) -> Result<Option<Vec<u8>>, String> {
+    let out = match bytes {
+        [0x1f, 0x8b, ..] => read_limited(GzDecoder::new(bytes), MAX_DECOMPRESSED_BYTES),
+        [0x78, 0x01 | 0x5e | 0x9c | 0xda, ..] => {
+            read_limited(ZlibDecoder::new(bytes), MAX_DECOMPRESSED_BYTES)
+        }
+        [0x28, 0xb5, 0x2f, 0xfd, ..] => return Err(unsupported("zstd")),
+        [0xfd, b'7', b'z', b'X', b'Z', 0x00, ..] => return Err(unsupported("xz")),
+        [b'B', b'Z', b'h', ..] => return Err(unsupported("bzip2")),
+        [b'P', b'K', 0x03, 0x04, ..] => return Err(unsupported("zip archive")),
+        _ => return Ok(None),
+    };
+    out.map(Some)
+}
+
+// Read all of a decompressed stream, failing rather than truncating output over `limit`
+fn read_limited(reader: impl Read, limit: u64) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    reader
+        .take(limit.saturating_add(1))
+        .read_to_end(&mut out)
+        .map_err(|e| format!("decompression failed: {e}"))?;
+    if out.len() as u64 > limit {
+        return Err(format!("decompressed file is larger than {limit} bytes"));
+    }
+    Ok(out)
+}
+
+fn unsupported(format: &str) -> String {
+    format!("unsupported compression: {format}; decompress the file before dropping it")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CPU_TYPE_X86_64: u32 = 0x0100_0007;
+    const CPU_TYPE_ARM64: u32 = 0x0100_000c;
+
+    // A thin 64-bit Mach-O executable whose only load command is LC_UUID
+    fn thin_macho(cpu_type: u32, cpu_subtype: u32, uuid: [u8; 16]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for field in [0xfeed_facf, cpu_type, cpu_subtype, 2, 1, 24, 0, 0] {
+            bytes.extend_from_slice(&u32::to_le_bytes(field));
+        }
+        bytes.extend_from_slice(&0x1bu32.to_le_bytes()); // LC_UUID
+        bytes.extend_from_slice(&24u32.to_le_bytes());
+        bytes.extend_from_slice(&uuid);
+        bytes
+    }
+
+    // A universal binary holding `slices`; its headers are big-endian
+    fn fat_macho(slices: &[(u32, u32, Vec<u8>)]) -> Vec<u8> {
+        const SLICE_ALIGN: usize = 0x100;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&0xcafe_babeu32.to_be_bytes());
+        bytes.extend_from_slice(&(slices.len() as u32).to_be_bytes());
+        let mut data = Vec::new();
+        for (cpu_type, cpu_subtype, slice) in slices {
+            let offset = SLICE_ALIGN * (1 + data.len().div_ceil(SLICE_ALIGN));
+            for field in [
+                *cpu_type,
+                *cpu_subtype,
+                offset as u32,
+                slice.len() as u32,
+                8,
+            ] {
+                bytes.extend_from_slice(&field.to_be_bytes());
+            }
+            data.resize(offset - SLICE_ALIGN, 0);
+            data.extend_from_slice(slice);
+        }
+        bytes.resize(SLICE_ALIGN, 0);
+        bytes.extend_from_slice(&data);
+        bytes
+    }
+
+    #[test]
+    fn decompresses_gzip_and_rejects_other_compression() {
+        use flate2::Compression;
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let object = thin_macho(CPU_TYPE_ARM64, 0, [0x22; 16]);
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&object).unwrap();
+        let gzip = encoder.finish().unwrap();
+        assert_eq!(decompress(&gzip).unwrap(), Some(object.clone()));
+        let metas = parse_dif_metadata(&gzip).unwrap();
+        assert_eq!(
+            metas[0].debug_id.as_deref(),
+            Some("22222222-2222-2222-2222-222222222222")
+        );
+        assert_eq!(decompress(&object).unwrap(), None);
+
+        let error = parse_dif_metadata(&[0x28, 0xb5, 0x2f, 0xfd, 0, 0])
+            .err()
+            .unwrap();
+        assert!(
+            error.starts_with("unsupported compression: zstd"),
+            "{}",
+            error
+        );
+
+        // Output over the limit is an error, not a truncated file
+        assert_eq!(
+            read_limited(&object[..], object.len() as u64),
+            Ok(object.clone())
+        );
+        assert!(read_limited(&object[..], object.len() as u64 - 1).is_err());
+    }
+
+    #[test]
+    fn lists_each_slice_of_a_universal_binary() {
+        let fat = fat_macho(&[
+            (
+                CPU_TYPE_X86_64,
+                3,
+                thin_macho(CPU_TYPE_X86_64, 3, [0x11; 16]),
+            ),
+            (CPU_TYPE_ARM64, 0, thin_macho(CPU_TYPE_ARM64, 0, [0x22; 16])),
+        ]);
+        let metas = parse_dif_metadata(&fat).unwrap();
+        let slices: Vec<(&str, Option<&str>, Option<&str>)> = metas
+            .iter()
+            .map(|m| (m.kind.as_str(), m.arch.as_deref(), m.debug_id.as_deref()))
+            .collect();
+        assert_eq!(
+            slices,
+            [
+                (
+                    "Executable",
+                    Some("x86_64"),
+                    Some("11111111-1111-1111-1111-111111111111")
+                ),
+                (
+                    "Executable",
+                    Some("arm64"),
+                    Some("22222222-2222-2222-2222-222222222222")
+                ),
+            ]
+        );
+
+        // A thin file is a one-entry list
+        let thin = parse_dif_metadata(&thin_macho(CPU_TYPE_ARM64, 0, [0x22; 16])).unwrap();
+        assert_eq!(thin.len(), 1);
+        assert_eq!(thin[0].debug_id, metas[1].debug_id);
+    }
+}
@@ -6,6 +6,12 @@ pub enum ViewerError {
     MinidumpRead(String),
     Serialization(String),
     DebugInfo(String),
+    Callback(String),
+    NotFound(String),
+    // The dump type never captures this kind of memory, e.g. heap memory in a stacks-only dump
+    MemoryNotCaptured(String),
+    // The dump does capture such memory, just not at this address
+    AddressNotCaptured(String),
 }
 
 impl std::fmt::Display for ViewerError {
@@ -14,6 +20,10 @@ impl std::fmt::Display for ViewerError {
             ViewerError::MinidumpRead(msg) => write!(f, "minidump read error: {}", msg),
             ViewerError::Serialization(msg) => write!(f, "serialization error: {}", msg),
             ViewerError::DebugInfo(msg) => write!(f, "debug info error: {}", msg),
+            ViewerError::Callback(msg) => write!(f, "callback error: {}", msg),
+            ViewerError::NotFound(msg) => write!(f, "not found: {}", msg),
+            ViewerError::MemoryNotCaptured(msg) => write!(f, "memory not captured: {}", msg),
+            ViewerError::AddressNotCaptured(msg) => write!(f, "address not captured: {}", msg),
         }
     }
 }
@@ -38,4 +48,10 @@ impl From<serde_wasm_bindgen::Error> for ViewerError {
     }
 }
 
+impl From<serde_json::Error> for ViewerError {
+    fn from(error: serde_json::Error) -> Self {
+        ViewerError::Serialization(error.to_string())
+    }
+}
+
 pub type Result<T> = std::result::Result<T, ViewerError>;
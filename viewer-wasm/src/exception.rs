@@ -1,6 +1,17 @@
-use crate::common::SafeU64;
-use crate::context::{StructuredContext, parse_context_registers};
-use minidump::{MinidumpException, MinidumpSystemInfo};
+use crate::common::{SafeU64, read_u32_le, read_u64_le};
+use crate::context::{
+    StructuredContext, context_matches_cpu, parse_context_registers, parse_extended_context,
+};
+use crate::memory::memory_at;
+use crate::options::ParseOptions;
+use crate::system_info::infer_system_info;
+use minidump::{
+    MinidumpContext, MinidumpException, MinidumpMiscInfo, MinidumpModuleList, MinidumpSystemInfo,
+    UnifiedMemoryList,
+};
+use minidump_unwind::SymbolProvider;
+use minidump_unwind::symbols::FrameSymbolizer;
+use minidump_unwind::symbols::debuginfo::DebugInfoSymbolProvider;
 use serde::Serialize;
 
 #[derive(Serialize)]
@@ -8,6 +19,7 @@ pub struct ExceptionRecord {
     // MINIDUMP_EXCEPTION fields (nested inside MINIDUMP_EXCEPTION_STREAM)
     pub exception_code: u32,
     pub exception_flags: u32,
+    pub exception_flags_decoded: Vec<String>, // Named EXCEPTION_* bits of exception_flags
     pub exception_record: SafeU64,
     pub exception_address: SafeU64,
     pub number_parameters: u32,
@@ -19,27 +31,60 @@ pub struct ExceptionStreamRaw {
     // MINIDUMP_EXCEPTION_STREAM fields
     pub thread_id: u32,
     pub exception_record: ExceptionRecord,
-    // Note: thread_context is a MINIDUMP_LOCATION_DESCRIPTOR, but the actual context
-    // is accessed via the high-level context() method, not raw field access
+    // thread_context MINIDUMP_LOCATION_DESCRIPTOR, for decoding the context bytes directly.
+    // The decoded context is `ExceptionData.context`.
+    pub thread_context_rva: u32,
+    pub thread_context_size: u32,
 }
 
 #[derive(Serialize)]
 pub struct ExceptionData {
-    pub crash_reason: Option<String>,       // from get_crash_reason()
-    pub crash_address: Option<SafeU64>,     // from get_crash_address()
-    pub thread_id: u32,                     // from get_crashing_thread_id()
-    pub context: Option<StructuredContext>, // structured register data
-    pub raw: Option<ExceptionStreamRaw>,    // properly nested raw structure
-    pub debug: Option<String>,              // raw debug output
-    pub context_debug: Option<String>,      // context debug output
+    pub crash_reason: Option<String>,          // from get_crash_reason()
+    pub crash_address: Option<SafeU64>,        // from get_crash_address()
+    pub faulting_instruction: Option<SafeU64>, // instruction pointer from the exception context
+    pub normalized_address: Option<SafeU64>, // faulting_instruction - module base, see ParseOptions
+    pub address_note: Option<String>,        // set when crash address != instruction pointer
+    // false when the context record is for a different CPU than SystemInfo reports
+    pub context_architecture_matches: bool,
+    pub in_page_error_status: Option<String>, // I/O NTSTATUS behind an EXCEPTION_IN_PAGE_ERROR
+    pub heap_corruption: bool,                // STATUS_HEAP_CORRUPTION raised by the heap manager
+    pub heap_failure_type: Option<String>,    // HEAP_FAILURE_TYPE from the first parameter
+    pub breakpoint_message: Option<String>,   // See read_breakpoint_message
+    pub faulting_registers: Vec<String>,      // See registers_holding_fault_address
+    // Nested EXCEPTION_RECORDs reachable in captured memory, see nested_exception_depth
+    pub has_nested_exception: bool,
+    pub nested_depth: u32,
+    // Return addresses read from the CET shadow stack, with `read_shadow_stack`
+    pub shadow_stack: Option<Vec<ShadowStackEntry>>,
+    pub nearest_symbol: Option<NearestSymbol>, // symbol at or below the crash address
+    pub thread_id: u32,                        // from get_crashing_thread_id()
+    pub context: Option<StructuredContext>,    // structured register data
+    pub raw: Option<ExceptionStreamRaw>,       // properly nested raw structure
+    pub debug: Option<String>,                 // raw debug output
+    pub context_debug: Option<String>,         // context debug output
+}
+
+#[derive(Serialize)]
+pub struct ShadowStackEntry {
+    pub return_address: SafeU64,
+    pub module_name: Option<String>, // From the module list; None for tokens and stray values
+}
+
+#[derive(Serialize)]
+pub struct NearestSymbol {
+    pub name: String,
+    pub offset: u64, // crash address - symbol base
 }
 
 pub fn parse_exception_info(
     exception: &MinidumpException,
     system: Option<&MinidumpSystemInfo>,
+    misc_info: Option<&MinidumpMiscInfo>,
+    dump_bytes: &[u8],
+    options: &ParseOptions,
 ) -> ExceptionData {
     // Get crash reason and address if we have system info for context
-    let (crash_reason, crash_address) = system
+    let (crash_reason, crash_address): (Option<String>, Option<SafeU64>) = system
         .map(|s| {
             (
                 Some(exception.get_crash_reason(s.os, s.cpu).to_string()),
@@ -49,21 +94,99 @@ pub fn parse_exception_info(
         .unwrap_or((None, None));
 
     // Get structured context if we have system info
-    let (context, context_debug_fallback) = system
-        .and_then(|s| exception.context(s, None))
-        .map(|c| (Some(parse_context_registers(&c)), Some(format!("{:#?}", c))))
+    // The minidump crate reads the context with the SystemInfo CPU's layout and rejects one
+    // whose flags name another CPU; read that one with its own layout so the mismatch shows
+    let raw_context = system.and_then(|s| {
+        exception.context(s, misc_info).or_else(|| {
+            let own_system = infer_system_info(dump_bytes, &exception.raw.thread_context)?;
+            exception.context(&own_system, misc_info)
+        })
+    });
+    let faulting_instruction = raw_context.as_ref().map(|c| c.get_instruction_pointer());
+    let context_architecture_matches = match (raw_context.as_deref(), system) {
+        (Some(context), Some(system)) => context_matches_cpu(context, system.cpu),
+        _ => true,
+    };
+    let faulting_registers = raw_context
+        .as_deref()
+        .map(|context| registers_holding_fault_address(exception, context))
+        .unwrap_or_default();
+    let (context, context_debug_fallback) = raw_context
+        .map(|c| {
+            let context_debug = options
+                .include_exception_context_debug
+                .then(|| format!("{:#?}", c));
+            let mut context = parse_context_registers(&c, options.register_raw_bytes);
+            // Extended (XSTATE) registers live past the classic context, so read the raw bytes
+            let location = &exception.raw.thread_context;
+            let start = location.rva as usize;
+            let context_bytes = start
+                .checked_add(location.data_size as usize)
+                .and_then(|end| dump_bytes.get(start..end));
+            if let Some(context_bytes) = context_bytes {
+                parse_extended_context(
+                    &mut context,
+                    &c,
+                    context_bytes,
+                    misc_info,
+                    options.register_raw_bytes,
+                );
+            }
+            (Some(context), context_debug)
+        })
         .unwrap_or((None, None));
 
-    // Extract exception information array (only valid elements)
-    let exception_information = exception.raw.exception_record.exception_information
-        [0..exception.raw.exception_record.number_parameters as usize]
+    // For data-access faults the crash address is the data address, not the code address
+    let address_note = match (crash_address.as_ref(), faulting_instruction) {
+        (Some(address), Some(ip)) if address.raw_value() != ip => Some(format!(
+            "crash address {} is the faulting data address; the faulting instruction is at {:#x}",
+            address.to_hex_string(),
+            ip
+        )),
+        _ => None,
+    };
+
+    // Extract exception information array (only valid elements). number_parameters comes
+    // straight from the dump, so don't trust it beyond the array's fixed size.
+    let exception_information: Vec<SafeU64> = exception
+        .raw
+        .exception_record
+        .exception_information
         .iter()
+        .take(exception.raw.exception_record.number_parameters as usize)
         .map(|&v| v.into())
         .collect();
 
+    let in_page_error_status = match exception.raw.exception_record.exception_code {
+        EXCEPTION_IN_PAGE_ERROR => exception_information
+            .get(2)
+            .map(|status| ntstatus_name(status.raw_value() as u32)),
+        _ => None,
+    };
+
+    let heap_corruption = exception.raw.exception_record.exception_code == STATUS_HEAP_CORRUPTION;
+    let heap_failure_type = exception_information
+        .first()
+        .filter(|_| heap_corruption)
+        .and_then(|failure_type| heap_failure_type_name(failure_type.raw_value()))
+        .map(str::to_string);
+
     ExceptionData {
         crash_reason,
         crash_address,
+        faulting_instruction: faulting_instruction.map(Into::into),
+        normalized_address: None, // Needs the module list, filled in by the caller
+        address_note,
+        context_architecture_matches,
+        in_page_error_status,
+        heap_corruption,
+        heap_failure_type,
+        breakpoint_message: None, // Needs the memory list, see read_breakpoint_message
+        faulting_registers,
+        has_nested_exception: false, // Needs the memory list, see nested_exception_depth
+        nested_depth: 0,
+        shadow_stack: None,   // Needs the memory list, see read_shadow_stack
+        nearest_symbol: None, // Needs the (async) symbol provider, see resolve_nearest_symbol
         thread_id: exception.get_crashing_thread_id(),
         context,
         raw: Some(ExceptionStreamRaw {
@@ -71,13 +194,463 @@ pub fn parse_exception_info(
             exception_record: ExceptionRecord {
                 exception_code: exception.raw.exception_record.exception_code,
                 exception_flags: exception.raw.exception_record.exception_flags,
+                exception_flags_decoded: decode_exception_flags(
+                    exception.raw.exception_record.exception_flags,
+                ),
                 exception_record: exception.raw.exception_record.exception_record.into(),
                 exception_address: exception.raw.exception_record.exception_address.into(),
                 number_parameters: exception.raw.exception_record.number_parameters,
                 exception_information,
             },
+            thread_context_rva: exception.raw.thread_context.rva,
+            thread_context_size: exception.raw.thread_context.data_size,
         }),
-        debug: Some(format!("{:#?}", exception)),
+        debug: options
+            .include_exception_debug
+            .then(|| format!("{:#?}", exception)),
         context_debug: context_debug_fallback,
     }
 }
+
+// EXCEPTION_RECORD.ExceptionFlags bits (winnt.h)
+const EXCEPTION_FLAGS: &[(u32, &str)] = &[
+    (0x01, "EXCEPTION_NONCONTINUABLE"),
+    (0x02, "EXCEPTION_UNWINDING"),
+    (0x04, "EXCEPTION_EXIT_UNWIND"),
+    (0x08, "EXCEPTION_STACK_INVALID"),
+    (0x10, "EXCEPTION_NESTED_CALL"),
+    (0x20, "EXCEPTION_TARGET_UNWIND"),
+    (0x40, "EXCEPTION_COLLIDED_UNWIND"),
+    (0x80, "EXCEPTION_SOFTWARE_ORIGINATE"),
+];
+
+fn decode_exception_flags(flags: u32) -> Vec<String> {
+    let mut decoded: Vec<String> = EXCEPTION_FLAGS
+        .iter()
+        .filter(|&&(bit, _)| flags & bit != 0)
+        .map(|&(_, name)| name.to_string())
+        .collect();
+
+    let known = EXCEPTION_FLAGS.iter().fold(0, |acc, &(bit, _)| acc | bit);
+    if flags & !known != 0 {
+        decoded.push(format!("UNKNOWN(0x{:x})", flags & !known));
+    }
+    decoded
+}
+
+pub(crate) const EXCEPTION_ACCESS_VIOLATION: u32 = 0xC000_0005;
+const EXCEPTION_IN_PAGE_ERROR: u32 = 0xC000_0006;
+
+// The registers of the crashing context that hold the inaccessible address of an access
+// violation or in-page error (ExceptionInformation[1]), i.e. the pointer that was bad, as in
+// "rax held 0x0". Only registers the context marks valid are considered.
+fn registers_holding_fault_address(
+    exception: &MinidumpException,
+    context: &MinidumpContext,
+) -> Vec<String> {
+    let record = &exception.raw.exception_record;
+    if !matches!(
+        record.exception_code,
+        EXCEPTION_ACCESS_VIOLATION | EXCEPTION_IN_PAGE_ERROR
+    ) || record.number_parameters < 2
+    {
+        return Vec::new();
+    }
+    let address = record.exception_information[1];
+    context
+        .valid_registers()
+        .filter(|&(_, value)| value == address)
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+// NTSTATUS values an in-page error commonly carries: the status of the failed paging I/O
+const IN_PAGE_ERROR_STATUS_NAMES: &[(u32, &str)] = &[
+    (0xC000_0008, "STATUS_INVALID_HANDLE"),
+    (0xC000_000E, "STATUS_NO_SUCH_DEVICE"),
+    (0xC000_0010, "STATUS_INVALID_DEVICE_REQUEST"),
+    (0xC000_0011, "STATUS_END_OF_FILE"),
+    (0xC000_0017, "STATUS_NO_MEMORY"),
+    (0xC000_0022, "STATUS_ACCESS_DENIED"),
+    (0xC000_0032, "STATUS_DISK_CORRUPT_ERROR"),
+    (0xC000_0034, "STATUS_OBJECT_NAME_NOT_FOUND"),
+    (0xC000_003A, "STATUS_OBJECT_PATH_NOT_FOUND"),
+    (0xC000_0043, "STATUS_SHARING_VIOLATION"),
+    (0xC000_0056, "STATUS_DELETE_PENDING"),
+    (0xC000_007F, "STATUS_DISK_FULL"),
+    (0xC000_009A, "STATUS_INSUFFICIENT_RESOURCES"),
+    (0xC000_009C, "STATUS_DEVICE_DATA_ERROR"),
+    (0xC000_009D, "STATUS_DEVICE_NOT_CONNECTED"),
+    (0xC000_00B5, "STATUS_IO_TIMEOUT"),
+    (0xC000_00C4, "STATUS_UNEXPECTED_NETWORK_ERROR"),
+    (0xC000_0102, "STATUS_FILE_CORRUPT_ERROR"),
+    (0xC000_0128, "STATUS_FILE_CLOSED"),
+    (0xC000_012D, "STATUS_COMMITMENT_LIMIT"),
+    (0xC000_016A, "STATUS_DISK_OPERATION_FAILED"),
+    (0xC000_0185, "STATUS_IO_DEVICE_ERROR"),
+    (0xC000_020C, "STATUS_CONNECTION_DISCONNECTED"),
+    (0xC000_026E, "STATUS_VOLUME_DISMOUNTED"),
+];
+
+// Name of an in-page error status, or its hex value when it isn't one we know
+fn ntstatus_name(status: u32) -> String {
+    IN_PAGE_ERROR_STATUS_NAMES
+        .iter()
+        .find(|&&(value, _)| value == status)
+        .map_or_else(
+            || format!("0x{:08x}", status),
+            |&(_, name)| name.to_string(),
+        )
+}
+
+// Raised by the NT heap (RtlReportCriticalFailure) when it detects corrupted metadata
+pub(crate) const STATUS_HEAP_CORRUPTION: u32 = 0xC000_0374;
+
+// HEAP_FAILURE_TYPE, indexed by value, as reported by `!heap -s` for heap failures
+const HEAP_FAILURE_TYPES: &[&str] = &[
+    "heap_failure_internal",
+    "heap_failure_unknown",
+    "heap_failure_generic",
+    "heap_failure_entry_corruption",
+    "heap_failure_multiple_entries_corruption",
+    "heap_failure_virtual_block_corruption",
+    "heap_failure_buffer_overrun",
+    "heap_failure_buffer_underrun",
+    "heap_failure_block_not_busy",
+    "heap_failure_invalid_argument",
+    "heap_failure_usage_after_free",
+    "heap_failure_cross_heap_operation",
+    "heap_failure_freelists_corruption",
+    "heap_failure_listentry_corruption",
+];
+
+// The first parameter is the failure type on some Windows versions and a pointer to the
+// heap's failure record on others; only values that fit the table are decoded
+fn heap_failure_type_name(value: u64) -> Option<&'static str> {
+    usize::try_from(value)
+        .ok()
+        .and_then(|index| HEAP_FAILURE_TYPES.get(index))
+        .copied()
+}
+
+pub(crate) const STATUS_BREAKPOINT: u32 = 0x8000_0003;
+const STATUS_FAIL_FAST_EXCEPTION: u32 = 0xC000_0602;
+
+// Upper bound on the bytes read for `breakpoint_message`
+const MAX_BREAKPOINT_MESSAGE_BYTES: usize = 1024;
+
+// Breakpoints and fail-fast exceptions raised by assert and reporting helpers sometimes pass a
+// message buffer as the first parameter. Read it as a NUL-terminated string when that memory
+// was captured; no terminator within the bound keeps the bounded prefix. Anything with
+// control characters other than whitespace is taken to be some other kind of parameter.
+pub fn read_breakpoint_message(
+    exception: &ExceptionData,
+    memory: &UnifiedMemoryList,
+) -> Option<String> {
+    let record = &exception.raw.as_ref()?.exception_record;
+    if !matches!(
+        record.exception_code,
+        STATUS_BREAKPOINT | STATUS_FAIL_FAST_EXCEPTION
+    ) {
+        return None;
+    }
+    let address = record.exception_information.first()?.raw_value();
+    if address == 0 {
+        return None;
+    }
+
+    let bytes = memory_at(memory, address, MAX_BREAKPOINT_MESSAGE_BYTES)?;
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    let message = String::from_utf8_lossy(&bytes[..end]);
+    let plausible = !message.trim().is_empty()
+        && message
+            .chars()
+            .all(|c| !c.is_control() || c.is_ascii_whitespace());
+    plausible.then(|| message.into_owned())
+}
+
+// Upper bound on the nested records followed by nested_exception_depth, which also stops
+// a chain that loops back on itself
+const MAX_NESTED_EXCEPTIONS: u32 = 16;
+
+// EXCEPTION_RECORD.ExceptionRecord follows the u32 code and flags in both the 32 and 64-bit
+// layouts
+const EXCEPTION_RECORD_CHAIN_OFFSET: u64 = 8;
+
+// How many records the exception's ExceptionRecord chain links to, counting only those whose
+// link field is in captured memory. Cheap enough to always run: one pointer read per record,
+// without decoding the records themselves.
+pub fn nested_exception_depth(
+    exception: &ExceptionData,
+    memory: &UnifiedMemoryList,
+    pointer_size: usize,
+) -> u32 {
+    let Some(raw) = exception.raw.as_ref() else {
+        return 0;
+    };
+    let mut address = raw.exception_record.exception_record.raw_value();
+    let mut depth = 0;
+    while address != 0 && depth < MAX_NESTED_EXCEPTIONS {
+        let next = address
+            .checked_add(EXCEPTION_RECORD_CHAIN_OFFSET)
+            .and_then(|link| memory_at(memory, link, pointer_size))
+            .and_then(|bytes| match pointer_size {
+                4 => read_u32_le(bytes, 0).map(u64::from),
+                _ => read_u64_le(bytes, 0),
+            });
+        let Some(next) = next else { break };
+        depth += 1;
+        address = next;
+    }
+    depth
+}
+
+// Upper bound on the entries read by read_shadow_stack
+const MAX_SHADOW_STACK_ENTRIES: usize = 256;
+
+// With CET, the CPU pushes every return address to a separate shadow stack as well, at the
+// context's `ssp` (decoded from XSTATE by parse_extended_context, AMD64 only). When the
+// normal stack is smashed the shadow stack still lists the real callers, innermost first,
+// so this is an alternate stack trace. Reads 8-byte entries up from ssp for as long as they
+// were captured; restore tokens and other non-code values show up without a module. None
+// without a valid ssp or when no shadow stack memory was captured.
+pub fn read_shadow_stack(
+    exception: &ExceptionData,
+    memory: &UnifiedMemoryList,
+    modules: Option<&MinidumpModuleList>,
+) -> Option<Vec<ShadowStackEntry>> {
+    let ssp = exception
+        .context
+        .as_ref()?
+        .other
+        .iter()
+        .find(|register| register.name == "ssp" && register.valid)?
+        .value
+        .raw_value();
+    if ssp == 0 {
+        return None;
+    }
+
+    let bytes = memory_at(memory, ssp, MAX_SHADOW_STACK_ENTRIES * 8)?;
+    let entries: Vec<ShadowStackEntry> = bytes
+        .chunks_exact(8)
+        .filter_map(|entry| read_u64_le(entry, 0))
+        .map(|address| ShadowStackEntry {
+            return_address: address.into(),
+            module_name: modules
+                .and_then(|modules| modules.module_at_address(address))
+                .map(|module| module.name.clone()),
+        })
+        .collect();
+    (!entries.is_empty()).then_some(entries)
+}
+
+// Resolve the function at or below `address` using the same symbol provider as unwinding,
+// e.g. to show a crash as "libfoo!Foo::bar+0x40". None without symbols for that module.
+pub async fn resolve_nearest_symbol(
+    address: u64,
+    system: &MinidumpSystemInfo,
+    modules: &MinidumpModuleList,
+) -> Option<NearestSymbol> {
+    use minidump::system_info::Cpu;
+
+    // DebugInfoSymbolProvider only supports these architectures (see extract_stack_frames_async)
+    if !matches!(system.cpu, Cpu::X86_64 | Cpu::Arm64) {
+        return None;
+    }
+    let module = modules.module_at_address(address)?;
+    let provider = DebugInfoSymbolProvider::new(system, modules).await;
+
+    let mut frame = SymbolLookup {
+        instruction: address,
+        function: None,
+    };
+    provider.fill_symbol(module, &mut frame).await.ok()?;
+    let (name, base) = frame.function?;
+
+    Some(NearestSymbol {
+        name,
+        offset: address.saturating_sub(base),
+    })
+}
+
+// Minimal FrameSymbolizer that only records the function
+struct SymbolLookup {
+    instruction: u64,
+    function: Option<(String, u64)>,
+}
+
+impl FrameSymbolizer for SymbolLookup {
+    fn get_instruction(&self) -> u64 {
+        self.instruction
+    }
+
+    fn set_function(&mut self, name: &str, base: u64, _parameter_size: u32) {
+        self.function = Some((name.to_string(), base));
+    }
+
+    fn set_source_file(&mut self, _file: &str, _line: u32, _base: u64) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        AMD64_CONTEXT_SIZE, DumpBuilder, Exception, PLATFORM_WIN32_NT,
+        PROCESSOR_ARCHITECTURE_AMD64, PROCESSOR_ARCHITECTURE_ARM64, SystemInfo, amd64_context,
+        put_u32, put_u64,
+    };
+    use minidump::Minidump;
+
+    const EXCEPTION_IP: u64 = 0x1_4000_1234;
+    const MISC_INFO_STREAM: u32 = 15;
+
+    // The exception of a Windows amd64 dump holding just `exception` (and a MiscInfo stream),
+    // as the overview shows it
+    fn exception_data(exception: Exception, misc_info: Option<Vec<u8>>) -> ExceptionData {
+        windows_exception_data(PROCESSOR_ARCHITECTURE_AMD64, exception, misc_info)
+    }
+
+    fn windows_exception_data(
+        architecture: u16,
+        exception: Exception,
+        misc_info: Option<Vec<u8>>,
+    ) -> ExceptionData {
+        let mut builder = DumpBuilder::new()
+            .system_info(SystemInfo::new(architecture, PLATFORM_WIN32_NT))
+            .exception(exception);
+        if let Some(misc_info) = misc_info {
+            builder = builder.stream(MISC_INFO_STREAM, misc_info);
+        }
+        let bytes = builder.build();
+        let dump = Minidump::read(bytes.as_slice()).unwrap();
+        let exception = dump.get_stream::<MinidumpException>().unwrap();
+        let system = dump.get_stream::<MinidumpSystemInfo>().ok();
+        let misc_info = dump.get_stream::<MinidumpMiscInfo>().ok();
+        parse_exception_info(
+            &exception,
+            system.as_ref(),
+            misc_info.as_ref(),
+            &bytes,
+            &ParseOptions::default(),
+        )
+    }
+
+    fn amd64_exception(code: u32, parameters: Vec<u64>) -> Exception {
+        let context = amd64_context(EXCEPTION_IP, 0x10_0100, 0x10_0200);
+        let mut exception = Exception::new(0x10, code, EXCEPTION_IP, context);
+        exception.parameters = parameters;
+        exception
+    }
+
+    #[test]
+    fn bounds_exception_parameters_by_the_array_size() {
+        // number_parameters says 20; EXCEPTION_MAXIMUM_PARAMETERS is 15
+        let exception =
+            exception_data(amd64_exception(STATUS_BREAKPOINT, (1..=20).collect()), None);
+        let record = exception.raw.unwrap().exception_record;
+        assert_eq!(record.number_parameters, 20);
+        let parameters: Vec<u64> = record
+            .exception_information
+            .iter()
+            .map(SafeU64::raw_value)
+            .collect();
+        assert_eq!(parameters, (1..=15).collect::<Vec<u64>>());
+
+        let exception = exception_data(amd64_exception(STATUS_BREAKPOINT, vec![7, 8]), None);
+        let record = exception.raw.unwrap().exception_record;
+        assert_eq!(record.exception_information.len(), 2);
+    }
+
+    #[test]
+    fn flags_a_context_from_another_cpu_than_system_info() {
+        let exception = exception_data(amd64_exception(STATUS_BREAKPOINT, vec![]), None);
+        assert!(exception.context_architecture_matches);
+
+        // An amd64 context in a dump whose SystemInfo says ARM64 is still decoded as amd64
+        let exception = windows_exception_data(
+            PROCESSOR_ARCHITECTURE_ARM64,
+            amd64_exception(STATUS_BREAKPOINT, vec![]),
+            None,
+        );
+        assert!(!exception.context_architecture_matches);
+        assert!(exception.context.is_some());
+    }
+
+    // XSAVE feature index of user CET state, and where in CONTEXT_AMD64 feature offsets
+    // count from
+    const XSTATE_CET_U: usize = 11;
+    const XSAVE_AREA_OFFSET: usize = 256;
+
+    // MINIDUMP_MISC_INFO_5 whose XSTATE_CONFIG_FEATURE_MSC_INFO enables user CET state at
+    // `cet_offset` into the XSAVE area
+    fn misc_info_with_cet(cet_offset: u32) -> Vec<u8> {
+        const MISC_INFO_5_SIZE: usize = 1364;
+        const XSTATE_DATA: usize = 832;
+        let mut bytes = vec![0u8; MISC_INFO_5_SIZE];
+        put_u32(&mut bytes, 0, MISC_INFO_5_SIZE as u32);
+        put_u32(&mut bytes, XSTATE_DATA, 528);
+        put_u64(&mut bytes, XSTATE_DATA + 8, 1 << XSTATE_CET_U);
+        let feature = XSTATE_DATA + 16 + XSTATE_CET_U * 8;
+        put_u32(&mut bytes, feature, cet_offset);
+        put_u32(&mut bytes, feature + 4, 16);
+        bytes
+    }
+
+    fn other_register(exception: &ExceptionData, name: &str) -> Option<(u64, bool)> {
+        let other = &exception.context.as_ref()?.other;
+        let register = other.iter().find(|register| register.name == name)?;
+        Some((register.value.raw_value(), register.valid))
+    }
+
+    #[test]
+    fn reads_cet_shadow_stack_registers_from_xstate() {
+        // The CET state right after the classic CONTEXT_AMD64
+        let mut context = amd64_context(EXCEPTION_IP, 0x10_0100, 0x10_0200);
+        context[48] |= 0x40; // CONTEXT_XSTATE
+        context.resize(AMD64_CONTEXT_SIZE + 16, 0);
+        put_u64(&mut context, AMD64_CONTEXT_SIZE, 0x1); // IA32_U_CET: shadow stack enabled
+        put_u64(&mut context, AMD64_CONTEXT_SIZE + 8, 0x7ff0_1000);
+        let mut exception = Exception::new(0x10, STATUS_BREAKPOINT, EXCEPTION_IP, context);
+
+        let cet_offset = (AMD64_CONTEXT_SIZE - XSAVE_AREA_OFFSET) as u32;
+        let data = exception_data(exception, Some(misc_info_with_cet(cet_offset)));
+        assert_eq!(
+            other_register(&data, "xstate_features"),
+            Some((1 << XSTATE_CET_U, true))
+        );
+        assert_eq!(other_register(&data, "u_cet"), Some((0x1, true)));
+        assert_eq!(other_register(&data, "ssp"), Some((0x7ff0_1000, true)));
+
+        // Without MiscInfo 5 the XSTATE flag is all there is to report
+        let mut context = amd64_context(EXCEPTION_IP, 0x10_0100, 0x10_0200);
+        context[48] |= 0x40;
+        exception = Exception::new(0x10, STATUS_BREAKPOINT, EXCEPTION_IP, context);
+        let data = exception_data(exception, None);
+        assert_eq!(other_register(&data, "xstate_features"), Some((0, false)));
+        assert_eq!(other_register(&data, "ssp"), None);
+    }
+
+    #[test]
+    fn decodes_heap_corruption_failure_types() {
+        let data = exception_data(amd64_exception(STATUS_HEAP_CORRUPTION, vec![10]), None);
+        assert!(data.heap_corruption);
+        assert_eq!(
+            data.heap_failure_type.as_deref(),
+            Some("heap_failure_usage_after_free")
+        );
+
+        // A failure record pointer rather than a type
+        let data = exception_data(
+            amd64_exception(STATUS_HEAP_CORRUPTION, vec![0x7ff7_1234_0000]),
+            None,
+        );
+        assert!(data.heap_corruption);
+        assert_eq!(data.heap_failure_type, None);
+
+        // Other exceptions never get a failure type
+        let data = exception_data(amd64_exception(STATUS_BREAKPOINT, vec![10]), None);
+        assert!(!data.heap_corruption);
+        assert_eq!(data.heap_failure_type, None);
+    }
+}
@@ -0,0 +1,42 @@
+use serde::Serialize;
+
+// MINIDUMP_STREAM_TYPE::JavaScriptDataStream, not parsed by the minidump crate
+pub const JAVASCRIPT_DATA_STREAM_TYPE: u32 = 20;
+
+#[derive(Serialize)]
+pub struct JavaScriptInfo {
+    pub data_size: usize,
+    pub encoding: String, // "utf-16le" or "utf-8"
+    pub text: String,     // Decoded payload (JS stack / V8 context), lossy for invalid data
+}
+
+// The stream layout isn't documented, so we treat the payload as text and pick
+// the encoding by looking at how it's laid out.
+pub fn parse_javascript_data(bytes: &[u8]) -> JavaScriptInfo {
+    let (encoding, text) = if looks_like_utf16le(bytes) {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        ("utf-16le", String::from_utf16_lossy(&units))
+    } else {
+        ("utf-8", String::from_utf8_lossy(bytes).into_owned())
+    };
+
+    JavaScriptInfo {
+        data_size: bytes.len(),
+        encoding: encoding.to_string(),
+        text: text.trim_end_matches('\0').to_string(),
+    }
+}
+
+// ASCII-range text encoded as UTF-16LE has a zero byte in most odd positions
+fn looks_like_utf16le(bytes: &[u8]) -> bool {
+    if bytes.len() < 2 || !bytes.len().is_multiple_of(2) {
+        return false;
+    }
+
+    let pairs = bytes.len() / 2;
+    let zero_high_bytes = bytes.iter().skip(1).step_by(2).filter(|&&b| b == 0).count();
+    zero_high_bytes * 2 > pairs
+}
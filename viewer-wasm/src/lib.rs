@@ -1,134 +1,718 @@
 use serde::Serialize;
+use std::collections::BTreeMap;
 use wasm_bindgen::prelude::*;
 
+mod address;
+mod annotations;
 mod common;
 mod context;
+mod crash;
 mod debug;
 mod errors;
 mod exception;
+mod javascript;
 mod memory;
+mod misc_info;
 mod modules;
+mod options;
+mod peb;
+mod priority;
+mod ranged;
+mod report;
+mod signature;
+mod streams;
 mod system_info;
+#[cfg(test)]
+mod testing;
 mod threads;
+mod time;
+mod token;
+mod version_check;
+mod vm_counters;
 
+use minidump::system_info::PointerWidth;
 use minidump::{
-    Minidump, MinidumpException, MinidumpMemoryInfoList, MinidumpModuleList, MinidumpSystemInfo,
-    MinidumpThreadList, MinidumpThreadNames,
+    Minidump, MinidumpBreakpadInfo, MinidumpException, MinidumpHandleDataStream,
+    MinidumpMemory64List, MinidumpMemoryInfoList, MinidumpMemoryList, MinidumpMiscInfo,
+    MinidumpModuleList, MinidumpStream, MinidumpSystemInfo, MinidumpThreadList,
+    MinidumpThreadNames, MinidumpUnloadedModuleList,
 };
 
+use address::{AddressSources, build_address_description};
+use annotations::{Annotation, collect_annotations};
+use crash::{
+    ASSERTION_INFO_STREAM_TYPE, CrashLocation, CrashSummary, build_crash_location,
+    build_crash_summary, crashing_thread_id, infer_capture_reason,
+};
 use errors::{Result, ViewerError};
-use exception::{ExceptionData, parse_exception_info};
-use memory::{MemoryData, parse_memory_data, parse_memory_info_data};
-use modules::{ModuleData, get_modules_count, parse_modules_data};
-use system_info::{SystemInfoData, parse_system_info};
-use threads::{ThreadData, parse_threads_data_async};
+use exception::{
+    ExceptionData, nested_exception_depth, parse_exception_info, read_breakpoint_message,
+    read_shadow_stack, resolve_nearest_symbol,
+};
+use javascript::{JAVASCRIPT_DATA_STREAM_TYPE, JavaScriptInfo, parse_javascript_data};
+use memory::{
+    MAX_RANGE_REGION_BYTES, MemoryData, load_memory, memory_at, missing_memory_error,
+    parse_memory_data, parse_memory_info_data, parse_memory_info_list_header,
+    parse_memory_info_raw, regions_in_range,
+};
+use misc_info::{MiscInfoData, parse_misc_info};
+use modules::{
+    ModuleData, ModuleIndex, SymbolModule, collect_required_symbols,
+    find_mixed_architecture_modules, get_modules_count, is_wine_dump, parse_modules_data,
+};
+use options::ParseOptions;
+use peb::read_process_parameters;
+use priority::{PriorityInversionCandidate, find_priority_inversions};
+use ranged::read_ranged_dump;
+use signature::build_crash_signature;
+use streams::{
+    DumpHeader, StreamDirectoryEntry, StreamPresence, parse_dump_header, parse_stream_directory,
+};
+use system_info::{ProcessorThreadCounts, SystemInfoData, infer_system_info, parse_system_info};
+use threads::{
+    StackFramesPage, ThreadData, ThreadParseContext, ThreadStack, find_matching_threads,
+    parse_thread_data_async, parse_threads_data_async, read_thread_stack,
+};
+use time::{DumpTime, build_dump_time};
+use token::{TOKEN_STREAM_TYPE, TokenInfo, parse_token_stream};
+use vm_counters::{PROCESS_VM_COUNTERS_STREAM_TYPE, ProcessVmCounters, parse_process_vm_counters};
+
+// "MDMP" signature at the start of every minidump header
+const MINIDUMP_MAGIC: &[u8] = b"MDMP";
+
+// Size of MINIDUMP_HEADER; anything shorter can't be a minidump
+const MINIDUMP_HEADER_SIZE: usize = 32;
+
+// Version of the `Overview` JSON layout. Bump it whenever a field is added, removed, renamed
+// or changes meaning, so archived snapshots from `overview_json` can be told apart.
+const SCHEMA_VERSION: u32 = 1;
 
 #[wasm_bindgen]
 pub async fn parse_minidump(bytes: &[u8]) -> std::result::Result<JsValue, JsValue> {
     console_error_panic_hook::set_once();
-    parse_minidump_internal(bytes).await.map_err(Into::into)
+    parse_minidump_internal(bytes, &ParseOptions::default())
+        .await
+        .map_err(Into::into)
+}
+
+// Same as `parse_minidump`, but accepts a `ParseOptions` object to enable optional analysis.
+#[wasm_bindgen]
+pub async fn parse_minidump_with_options(
+    bytes: &[u8],
+    options: JsValue,
+) -> std::result::Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    let options = parse_options(options).map_err(JsValue::from)?;
+    parse_minidump_internal(bytes, &options)
+        .await
+        .map_err(Into::into)
+}
+
+// Parse a minidump that starts `offset` bytes into `bytes`, e.g. a dump appended after a
+// container header. Avoids slicing (and copying) the buffer on the JS side.
+#[wasm_bindgen]
+pub async fn parse_minidump_at(
+    bytes: &[u8],
+    offset: usize,
+) -> std::result::Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    let dump_bytes = slice_at_offset(bytes, offset).map_err(JsValue::from)?;
+    parse_minidump_internal(dump_bytes, &ParseOptions::default())
+        .await
+        .map_err(Into::into)
+}
+
+// Parse a dump of `size` bytes that JS serves in pieces rather than as one buffer, for dumps
+// too big for wasm memory. `fetch(offset, length)` must return (or resolve to) a Uint8Array
+// or ArrayBuffer of exactly `length` bytes from that offset of the file. Dumps up to 512 MiB
+// are fetched whole and parsed like `parse_minidump`. Larger ones are parsed from a compact
+// copy holding the metadata and thread stacks only, so other memory reads as not captured;
+// see ranged::read_compact_dump.
+#[wasm_bindgen]
+pub async fn parse_minidump_ranged(
+    size: f64,
+    fetch: js_sys::Function,
+) -> std::result::Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    let bytes = read_ranged_dump(size as u64, &fetch)
+        .await
+        .map_err(JsValue::from)?;
+    parse_minidump_internal(&bytes, &ParseOptions::default())
+        .await
+        .map_err(Into::into)
+}
+
+// Parse a minidump and hand each section to `on_section(name, value)` as soon as it's ready:
+// "system_info", "exception_info", "modules_data", "memory_data", then one "thread" call per
+// thread in dump order (plus a synthesized crashing thread the thread list lacks), so the UI
+// can render while the remaining threads are still unwinding. Once every thread is in, the
+// analyses build_overview derives from them follow, named like their `Overview` fields:
+// "crash_summary", "capture_reason", "crash_signature", "crash_signature_hash",
+// "crash_location" and "priority_inversion_candidates".
+// `options` is the same optional `ParseOptions` object `parse_minidump_with_options` takes.
+// Resolves once every section has been delivered; use `parse_minidump` for the whole overview.
+#[wasm_bindgen]
+pub async fn parse_minidump_streaming(
+    bytes: &[u8],
+    on_section: js_sys::Function,
+    options: JsValue,
+) -> std::result::Result<(), JsValue> {
+    console_error_panic_hook::set_once();
+    let options = parse_options(options).map_err(JsValue::from)?;
+    parse_minidump_streaming_internal(bytes, &mut &on_section, &options)
+        .await
+        .map_err(Into::into)
+}
+
+async fn parse_minidump_streaming_internal(
+    bytes: &[u8],
+    on_section: &mut impl SectionSink,
+    options: &ParseOptions,
+) -> Result<()> {
+    let dump = read_minidump(bytes)?;
+    let streams = extract_minidump_streams(&dump, bytes);
+
+    let wine = streams.modules.as_ref().is_some_and(is_wine_dump);
+    let system_info = streams
+        .system
+        .as_ref()
+        .map(|system| parse_system_info(system, wine));
+    on_section.emit("system_info", &system_info)?;
+
+    let exception_info = build_exception_info(&streams, options).await;
+    on_section.emit("exception_info", &exception_info)?;
+
+    let modules_data = streams.modules.as_ref().map(|m| {
+        parse_modules_data(
+            m,
+            streams.data,
+            streams.memory.as_ref(),
+            streams.memory_info.as_ref(),
+            options,
+        )
+    });
+    on_section.emit("modules_data", &modules_data)?;
+
+    on_section.emit("memory_data", &build_memory_data(&streams, options))?;
+
+    let mut threads_data = Vec::new();
+    if let Some(threads) = streams.threads.as_ref() {
+        let ctx = ThreadParseContext::new(
+            threads,
+            streams.system.as_ref(),
+            streams.thread_names.as_ref(),
+            streams.modules.as_ref(),
+            &dump,
+            streams.memory.as_ref(),
+            options,
+        )
+        .with_crashing_thread(exception_info.as_ref().map(crashing_thread_id));
+        for thread in &threads.threads {
+            let thread_data = parse_thread_data_async(thread, &ctx).await;
+            on_section.emit("thread", &thread_data)?;
+            threads_data.push(thread_data);
+        }
+        if let Some(thread_data) = streams
+            .exception
+            .as_ref()
+            .and_then(|e| ctx.synthesize_crashing_thread(e))
+        {
+            on_section.emit("thread", &thread_data)?;
+            threads_data.push(thread_data);
+        }
+    }
+
+    let analysis = build_crash_analysis(
+        &dump,
+        streams.memory_info.as_ref(),
+        exception_info.as_ref(),
+        streams.threads.as_ref().map(|_| threads_data.as_slice()),
+        options,
+    );
+    on_section.emit("crash_summary", &analysis.crash_summary)?;
+    on_section.emit("capture_reason", &analysis.capture_reason)?;
+    on_section.emit("crash_signature", &analysis.crash_signature)?;
+    on_section.emit("crash_signature_hash", &analysis.crash_signature_hash)?;
+    on_section.emit("crash_location", &analysis.crash_location)?;
+    on_section.emit(
+        "priority_inversion_candidates",
+        &analysis.priority_inversion_candidates,
+    )?;
+
+    Ok(())
+}
+
+// Where the streaming parse delivers its sections: the JS callback, or a collector in tests
+trait SectionSink {
+    fn emit<T: Serialize>(&mut self, name: &str, value: &T) -> Result<()>;
+}
+
+impl SectionSink for &js_sys::Function {
+    fn emit<T: Serialize>(&mut self, name: &str, value: &T) -> Result<()> {
+        let value = serde_wasm_bindgen::to_value(value)?;
+        self.call2(&JsValue::NULL, &JsValue::from_str(name), &value)
+            .map_err(|e| ViewerError::Callback(format!("{:?}", e)))?;
+        Ok(())
+    }
+}
+
+fn slice_at_offset(bytes: &[u8], offset: usize) -> Result<&[u8]> {
+    let dump_bytes = bytes.get(offset..).ok_or_else(|| {
+        ViewerError::MinidumpRead(format!(
+            "offset {} is past the end of the {} byte buffer",
+            offset,
+            bytes.len()
+        ))
+    })?;
+
+    if !dump_bytes.starts_with(MINIDUMP_MAGIC) {
+        return Err(ViewerError::MinidumpRead(format!(
+            "no minidump signature found at offset {}",
+            offset
+        )));
+    }
+
+    Ok(dump_bytes)
+}
+
+// Minidump::read, with a clear error for empty or truncated input (e.g. a failed upload)
+// instead of the minidump crate's generic header error
+fn read_minidump(bytes: &[u8]) -> Result<Minidump<'_, &[u8]>> {
+    if bytes.len() < MINIDUMP_HEADER_SIZE {
+        return Err(ViewerError::MinidumpRead(
+            "input too small to be a minidump".to_string(),
+        ));
+    }
+    Ok(Minidump::read(bytes)?)
+}
+
+fn parse_options(options: JsValue) -> Result<ParseOptions> {
+    if options.is_undefined() || options.is_null() {
+        return Ok(ParseOptions::default());
+    }
+    Ok(serde_wasm_bindgen::from_value(options)?)
+}
+
+// The overview as pretty-printed JSON with object keys sorted, for archiving and diffing
+// analyses independently of the viewer version (see SCHEMA_VERSION)
+#[wasm_bindgen]
+pub async fn overview_json(bytes: &[u8]) -> std::result::Result<String, JsValue> {
+    console_error_panic_hook::set_once();
+    overview_json_internal(bytes).await.map_err(Into::into)
+}
+
+async fn overview_json_internal(bytes: &[u8]) -> Result<String> {
+    let dump = read_minidump(bytes)?;
+    let streams = extract_minidump_streams(&dump, bytes);
+    let overview = build_overview(&streams, &dump, &ParseOptions::default()).await?;
+    // serde_json's map is a BTreeMap, so going through a Value sorts every object's keys
+    let value = serde_json::to_value(&overview)?;
+    Ok(serde_json::to_string_pretty(&value)?)
 }
 
-async fn parse_minidump_internal(bytes: &[u8]) -> Result<JsValue> {
-    let dump = Minidump::read(bytes)?;
-    let streams = extract_minidump_streams(&dump);
-    let overview = build_overview(&streams, &dump).await?;
+async fn parse_minidump_internal(bytes: &[u8], options: &ParseOptions) -> Result<JsValue> {
+    let overview = parse_overview(bytes, options).await?;
     Ok(serde_wasm_bindgen::to_value(&overview)?)
 }
 
+async fn parse_overview(bytes: &[u8], options: &ParseOptions) -> Result<Overview> {
+    let dump = read_minidump(bytes)?;
+    let streams = extract_minidump_streams(&dump, bytes);
+    build_overview(&streams, &dump, options).await
+}
+
 #[derive(Debug)]
 struct MinidumpStreams<'a> {
+    data: &'a [u8], // Raw dump bytes, for records the minidump crate doesn't decode
     system: Option<MinidumpSystemInfo>,
+    system_inferred: bool, // `system` was derived from a thread context, see infer_system_info
     exception: Option<MinidumpException<'a>>,
     threads: Option<MinidumpThreadList<'a>>,
     thread_names: Option<MinidumpThreadNames>,
     modules: Option<MinidumpModuleList>,
+    unloaded_modules: Option<MinidumpUnloadedModuleList>,
     memory: Option<minidump::UnifiedMemoryList<'a>>,
     memory_info: Option<MinidumpMemoryInfoList<'a>>,
+    memory_info_raw: Option<&'a [u8]>,
+    handles: Option<MinidumpHandleDataStream>,
+    misc_info: Option<MinidumpMiscInfo>,
+    token: Option<&'a [u8]>,
+    javascript: Option<&'a [u8]>,
+    vm_counters: Option<&'a [u8]>,
 }
 
-fn extract_minidump_streams<'a>(dump: &'a Minidump<'a, &'a [u8]>) -> MinidumpStreams<'a> {
-    MinidumpStreams {
+fn extract_minidump_streams<'a>(
+    dump: &'a Minidump<'a, &'a [u8]>,
+    data: &'a [u8],
+) -> MinidumpStreams<'a> {
+    let mut streams = MinidumpStreams {
+        data,
         system: dump.get_stream::<MinidumpSystemInfo>().ok(),
+        system_inferred: false,
         exception: dump.get_stream::<MinidumpException>().ok(),
         threads: dump.get_stream::<MinidumpThreadList>().ok(),
         thread_names: dump.get_stream::<MinidumpThreadNames>().ok(),
         modules: dump.get_stream::<MinidumpModuleList>().ok(),
-        memory: dump.get_memory(),
+        unloaded_modules: dump.get_stream::<MinidumpUnloadedModuleList>().ok(),
+        memory: load_memory(dump, data),
         memory_info: dump.get_stream::<MinidumpMemoryInfoList>().ok(),
+        memory_info_raw: dump
+            .get_raw_stream(MinidumpMemoryInfoList::STREAM_TYPE)
+            .ok(),
+        handles: dump.get_stream::<MinidumpHandleDataStream>().ok(),
+        misc_info: dump.get_stream::<MinidumpMiscInfo>().ok(),
+        token: dump.get_raw_stream(TOKEN_STREAM_TYPE).ok(),
+        javascript: dump.get_raw_stream(JAVASCRIPT_DATA_STREAM_TYPE).ok(),
+        vm_counters: dump.get_raw_stream(PROCESS_VM_COUNTERS_STREAM_TYPE).ok(),
+    };
+
+    // Without a usable SystemInfo stream, fall back to the CPU implied by the exception (or
+    // first thread's) context so registers and stacks can still be decoded
+    if streams.system.is_none() {
+        let context = streams
+            .exception
+            .as_ref()
+            .map(|e| &e.raw.thread_context)
+            .or_else(|| {
+                let thread = streams.threads.as_ref()?.threads.first()?;
+                Some(&thread.raw.thread_context)
+            });
+        let inferred = context.and_then(|c| infer_system_info(data, c));
+        streams.system_inferred = inferred.is_some();
+        streams.system = inferred;
     }
+
+    streams
 }
 
-fn build_streams_present_list(streams: &MinidumpStreams) -> Vec<&'static str> {
-    let mut streams_present = Vec::new();
-    if streams.system.is_some() {
-        streams_present.push("SystemInfo");
-    }
-    if streams.exception.is_some() {
-        streams_present.push("Exception");
-    }
-    if streams.threads.is_some() {
-        streams_present.push("ThreadList");
-    }
-    if streams.modules.is_some() {
-        streams_present.push("ModuleList");
-    }
-    if streams.memory.is_some() {
-        streams_present.push("MemoryList");
+fn build_streams_present_list(
+    streams: &MinidumpStreams,
+    dump: &Minidump<'_, &[u8]>,
+) -> Vec<StreamPresence> {
+    // (name, stream types that can carry it, whether it was parsed)
+    let known: [(&str, &[u32], bool); 5] = [
+        (
+            "SystemInfo",
+            &[MinidumpSystemInfo::STREAM_TYPE],
+            streams.system.is_some() && !streams.system_inferred,
+        ),
+        (
+            "Exception",
+            &[MinidumpException::STREAM_TYPE],
+            streams.exception.is_some(),
+        ),
+        (
+            "ThreadList",
+            &[MinidumpThreadList::STREAM_TYPE],
+            streams.threads.is_some(),
+        ),
+        (
+            "ModuleList",
+            &[MinidumpModuleList::STREAM_TYPE],
+            streams.modules.is_some(),
+        ),
+        (
+            "MemoryList",
+            &[
+                MinidumpMemoryList::STREAM_TYPE,
+                MinidumpMemory64List::STREAM_TYPE,
+            ],
+            streams.memory.is_some(),
+        ),
+    ];
+
+    known
+        .iter()
+        .filter_map(|&(name, stream_types, parsed)| {
+            let detected = stream_types
+                .iter()
+                .copied()
+                .find(|&stream_type| dump.get_raw_stream(stream_type).is_ok());
+            if !parsed && detected.is_none() {
+                return None;
+            }
+            Some(StreamPresence {
+                name: name.to_string(),
+                stream_type: detected.unwrap_or(stream_types[0]),
+                parsed,
+            })
+        })
+        .collect()
+}
+
+// Classify how much of the process state the dump captured:
+// - "full": a Memory64 list is present (full-memory dump)
+// - "mini": thread and module lists are present, so stacks can be shown
+// - "micro": anything less, e.g. only system info and an exception
+fn classify_dump_completeness(streams: &MinidumpStreams) -> &'static str {
+    if matches!(
+        streams.memory,
+        Some(minidump::UnifiedMemoryList::Memory64(_))
+    ) {
+        "full"
+    } else if streams.threads.is_some() && streams.modules.is_some() {
+        "mini"
+    } else {
+        "micro"
     }
-    streams_present
 }
 
 async fn build_overview(
     streams: &MinidumpStreams<'_>,
     dump: &Minidump<'_, &[u8]>,
+    options: &ParseOptions,
 ) -> Result<Overview> {
-    let streams_present = build_streams_present_list(streams);
+    let streams_present = build_streams_present_list(streams, dump);
+    let header = parse_dump_header(dump);
+    let dump_time = build_dump_time(dump.header.time_date_stamp, streams.misc_info.as_ref());
+    let stream_directory = parse_stream_directory(dump);
+    // A valid dump with none of the streams we understand; the directory shows what it holds
+    let is_empty = !streams_present.iter().any(|stream| stream.parsed);
+    let dump_completeness = classify_dump_completeness(streams);
 
     // Parse individual components
-    let system_info = streams.system.as_ref().map(parse_system_info);
-    let exception_info = streams
-        .exception
+    let wine = streams.modules.as_ref().is_some_and(is_wine_dump);
+    let system_info = streams
+        .system
         .as_ref()
-        .map(|e| parse_exception_info(e, streams.system.as_ref()));
+        .map(|system| parse_system_info(system, wine));
+    let exception_info = build_exception_info(streams, options).await;
 
     let threads_data = if let Some(threads_ref) = streams.threads.as_ref() {
-        Some(
-            parse_threads_data_async(
-                threads_ref,
-                streams.system.as_ref(),
-                streams.thread_names.as_ref(),
-                streams.modules.as_ref(),
-                dump,
-            )
-            .await,
+        // The exception stream's thread_id, as crash::crashing_thread_id reports it
+        let ctx = ThreadParseContext::new(
+            threads_ref,
+            streams.system.as_ref(),
+            streams.thread_names.as_ref(),
+            streams.modules.as_ref(),
+            dump,
+            streams.memory.as_ref(),
+            options,
         )
+        .with_crashing_thread(streams.exception.as_ref().map(|e| e.raw.thread_id));
+        Some(parse_threads_data_async(&ctx, streams.exception.as_ref()).await)
     } else {
         None
     };
 
-    let modules_data = streams.modules.as_ref().map(parse_modules_data);
-    let memory_data = build_memory_data(streams);
+    let analysis = build_crash_analysis(
+        dump,
+        streams.memory_info.as_ref(),
+        exception_info.as_ref(),
+        threads_data.as_deref(),
+        options,
+    );
+
+    let modules_data = streams.modules.as_ref().map(|m| {
+        parse_modules_data(
+            m,
+            streams.data,
+            streams.memory.as_ref(),
+            streams.memory_info.as_ref(),
+            options,
+        )
+    });
+    let required_symbols =
+        collect_required_symbols(streams.modules.as_ref(), streams.unloaded_modules.as_ref());
+    let memory_data = build_memory_data(streams, options);
+    let mixed_architecture_modules = match (
+        streams.modules.as_ref(),
+        streams.memory.as_ref(),
+        streams.system.as_ref(),
+    ) {
+        (Some(modules), Some(memory), Some(system)) => {
+            find_mixed_architecture_modules(modules, memory, system.cpu)
+        }
+        _ => Vec::new(),
+    };
+    let processor_thread_counts = match (streams.system.as_ref(), streams.threads.as_ref()) {
+        (Some(system), Some(threads)) => Some(ProcessorThreadCounts {
+            processors: system.raw.number_of_processors,
+            threads: threads.threads.len(),
+        }),
+        _ => None,
+    };
+    let token_info = streams
+        .token
+        .and_then(|t| parse_token_stream(t, streams.handles.as_ref()));
+    let javascript_info = streams.javascript.map(parse_javascript_data);
+    let process_vm_counters = streams
+        .vm_counters
+        .and_then(|bytes| parse_process_vm_counters(bytes, options.size_units));
+    let process_parameters = match (&streams.system, &streams.threads, &streams.memory) {
+        (Some(system), Some(threads), Some(memory)) => {
+            read_process_parameters(system, threads, memory)
+        }
+        _ => None,
+    };
+    let (command_line, environment) = match process_parameters {
+        Some(params) => (params.command_line, params.environment),
+        None => (None, Vec::new()),
+    };
 
     Ok(Overview {
+        schema_version: SCHEMA_VERSION,
+        is_empty,
+        header,
+        dump_time,
         streams_present,
+        stream_directory,
+        dump_completeness,
         modules_count: streams.modules.as_ref().map(get_modules_count),
         threads_count: streams.threads.as_ref().map(|t| t.threads.len()),
+        processor_thread_counts,
         system_info,
+        system_info_inferred: streams.system_inferred,
+        misc_info: streams.misc_info.as_ref().map(parse_misc_info),
         exception_info,
+        crash_summary: analysis.crash_summary,
+        capture_reason: analysis.capture_reason,
+        crash_signature: analysis.crash_signature,
+        crash_signature_hash: analysis.crash_signature_hash,
+        crash_location: analysis.crash_location,
+        priority_inversion_candidates: analysis.priority_inversion_candidates,
         threads_data,
         modules_data,
+        mixed_architecture_modules,
+        required_symbols,
         memory_data,
+        token_info,
+        javascript_info,
+        process_vm_counters,
+        command_line,
+        environment,
+        annotations: collect_annotations(dump, streams.modules.as_ref()),
     })
 }
 
-fn build_memory_data(streams: &MinidumpStreams) -> Option<MemoryData> {
+// What build_overview derives from the exception and the parsed threads together; the
+// streaming parse sends these once its last thread is out
+struct CrashAnalysis {
+    crash_summary: Option<CrashSummary>,
+    capture_reason: &'static str,
+    crash_signature: Option<String>,
+    crash_signature_hash: Option<String>,
+    crash_location: Option<CrashLocation>,
+    priority_inversion_candidates: Vec<PriorityInversionCandidate>,
+}
+
+fn build_crash_analysis(
+    dump: &Minidump<'_, &[u8]>,
+    memory_info: Option<&MinidumpMemoryInfoList>,
+    exception_info: Option<&ExceptionData>,
+    threads_data: Option<&[ThreadData]>,
+    options: &ParseOptions,
+) -> CrashAnalysis {
+    let crashing_id = exception_info.map(crashing_thread_id);
+    // Dumps with an exception but no (or an empty) thread list still get a summary
+    let crash_summary = exception_info.map(|e| {
+        build_crash_summary(
+            e,
+            threads_data.unwrap_or_default(),
+            memory_info,
+            dump.get_stream::<MinidumpBreakpadInfo>().ok().as_ref(),
+        )
+    });
+    let capture_reason = infer_capture_reason(
+        exception_info,
+        dump.get_raw_stream(ASSERTION_INFO_STREAM_TYPE).is_ok(),
+    );
+    let crash_signature = match (threads_data, crashing_id) {
+        (Some(threads), Some(id)) => build_crash_signature(threads, id),
+        _ => None,
+    };
+    let crash_location = match (threads_data, crashing_id) {
+        (Some(threads), Some(id)) => build_crash_location(threads, id),
+        _ => None,
+    };
+    let priority_inversion_candidates = match threads_data {
+        Some(threads) if options.detect_priority_inversion => find_priority_inversions(threads),
+        _ => Vec::new(),
+    };
+
+    CrashAnalysis {
+        crash_summary,
+        capture_reason,
+        crash_signature_hash: crash_signature.as_ref().map(|s| s.hash.clone()),
+        crash_signature: crash_signature.map(|s| s.signature),
+        crash_location,
+        priority_inversion_candidates,
+    }
+}
+
+async fn build_exception_info(
+    streams: &MinidumpStreams<'_>,
+    options: &ParseOptions,
+) -> Option<ExceptionData> {
+    let mut exception_info = parse_exception_info(
+        streams.exception.as_ref()?,
+        streams.system.as_ref(),
+        streams.misc_info.as_ref(),
+        streams.data,
+        options,
+    );
+
+    if let (Some(modules), Some(ip)) = (
+        streams.modules.as_ref(),
+        exception_info.faulting_instruction.as_ref(),
+    ) && options.normalize_addresses
+    {
+        let ip = ip.raw_value();
+        exception_info.normalized_address = modules
+            .module_at_address(ip)
+            .map(|module| (ip - module.raw.base_of_image).into());
+    }
+
+    if let (Some(system), Some(modules), Some(address)) = (
+        streams.system.as_ref(),
+        streams.modules.as_ref(),
+        exception_info.crash_address.as_ref(),
+    ) {
+        exception_info.nearest_symbol =
+            resolve_nearest_symbol(address.raw_value(), system, modules).await;
+    }
+
+    if let Some(memory) = streams.memory.as_ref() {
+        exception_info.breakpoint_message = read_breakpoint_message(&exception_info, memory);
+        let pointer_size = match streams
+            .system
+            .as_ref()
+            .map(|system| system.cpu.pointer_width())
+        {
+            Some(PointerWidth::Bits32) => 4,
+            _ => 8,
+        };
+        exception_info.nested_depth = nested_exception_depth(&exception_info, memory, pointer_size);
+        exception_info.has_nested_exception = exception_info.nested_depth > 0;
+        if options.read_shadow_stack {
+            exception_info.shadow_stack =
+                read_shadow_stack(&exception_info, memory, streams.modules.as_ref());
+        }
+    }
+
+    Some(exception_info)
+}
+
+fn build_memory_data(streams: &MinidumpStreams, options: &ParseOptions) -> Option<MemoryData> {
     streams.memory.as_ref().map(|m| {
-        let mut memory_data = parse_memory_data(m);
+        let mut memory_data = parse_memory_data(
+            m,
+            streams.threads.as_ref(),
+            streams.thread_names.as_ref(),
+            options,
+        );
 
-        // Add memory info if available
+        // Add memory info if available, falling back to our own reader for entry layouts
+        // the minidump crate rejects
         if let Some(info) = streams.memory_info.as_ref() {
-            memory_data.memory_info = Some(parse_memory_info_data(info));
+            let mut memory_info = parse_memory_info_data(info, options);
+            memory_info.list_header = streams
+                .memory_info_raw
+                .and_then(parse_memory_info_list_header);
+            memory_data.memory_info = Some(memory_info);
+            memory_data.has_memory_info_stream = true;
+        } else if let Some(raw) = streams.memory_info_raw {
+            memory_data.memory_info = parse_memory_info_raw(raw, options);
             memory_data.has_memory_info_stream = true;
         }
 
@@ -138,14 +722,37 @@ fn build_memory_data(streams: &MinidumpStreams) -> Option<MemoryData> {
 
 #[derive(Serialize)]
 struct Overview {
-    streams_present: Vec<&'static str>,
+    schema_version: u32,
+    is_empty: bool,
+    header: DumpHeader,
+    dump_time: Option<DumpTime>,
+    streams_present: Vec<StreamPresence>,
+    stream_directory: Vec<StreamDirectoryEntry>,
+    dump_completeness: &'static str,
     modules_count: Option<usize>,
     threads_count: Option<usize>,
+    processor_thread_counts: Option<ProcessorThreadCounts>,
     system_info: Option<SystemInfoData>,
+    system_info_inferred: bool,
+    misc_info: Option<MiscInfoData>,
     exception_info: Option<ExceptionData>,
+    crash_summary: Option<CrashSummary>,
+    capture_reason: &'static str, // See infer_capture_reason
+    crash_signature: Option<String>,
+    crash_signature_hash: Option<String>,
+    crash_location: Option<CrashLocation>,
+    priority_inversion_candidates: Vec<PriorityInversionCandidate>, // With `detect_priority_inversion`
     threads_data: Option<Vec<ThreadData>>,
     modules_data: Option<ModuleData>,
+    mixed_architecture_modules: Vec<String>,
+    required_symbols: Vec<SymbolModule>, // Unique modules with a debug id, loaded and unloaded
     memory_data: Option<MemoryData>,
+    token_info: Option<Vec<TokenInfo>>,
+    javascript_info: Option<JavaScriptInfo>,
+    process_vm_counters: Option<ProcessVmCounters>,
+    command_line: Option<String>, // From the PEB, when that memory was captured
+    environment: Vec<String>,
+    annotations: Vec<Annotation>, // Crashpad, comment and assertion text, see collect_annotations
 }
 
 // Optional: prove `symbolic` compiles on Wasm and let users drop a PDB/ELF/Mach-O/Breakpad file
@@ -159,3 +766,612 @@ fn dif_metadata_internal(bytes: &[u8]) -> Result<JsValue> {
     let meta = debug::parse_dif_metadata(bytes).map_err(ViewerError::DebugInfo)?;
     Ok(serde_wasm_bindgen::to_value(&meta)?)
 }
+
+// Which module of `dump` the dropped `symbol` file belongs to, by debug identifier. The
+// result has `matched: false` when none does.
+#[wasm_bindgen]
+pub fn match_symbol_to_dump(dump: &[u8], symbol: &[u8]) -> std::result::Result<JsValue, JsValue> {
+    match_symbol_to_dump_internal(dump, symbol).map_err(Into::into)
+}
+
+fn match_symbol_to_dump_internal(dump: &[u8], symbol: &[u8]) -> Result<JsValue> {
+    let dump = read_minidump(dump)?;
+    let modules = dump.get_stream::<MinidumpModuleList>()?;
+    let symbol_match =
+        debug::match_symbol_to_modules(symbol, &modules).map_err(ViewerError::DebugInfo)?;
+    Ok(serde_wasm_bindgen::to_value(&symbol_match)?)
+}
+
+// Compare module versions in the dump against `expected`, a map of module name -> version.
+#[wasm_bindgen]
+pub fn check_module_versions(
+    bytes: &[u8],
+    expected: JsValue,
+) -> std::result::Result<JsValue, JsValue> {
+    check_module_versions_internal(bytes, expected).map_err(Into::into)
+}
+
+fn check_module_versions_internal(bytes: &[u8], expected: JsValue) -> Result<JsValue> {
+    let expected: BTreeMap<String, String> = serde_wasm_bindgen::from_value(expected)?;
+    let dump = read_minidump(bytes)?;
+    let modules = dump.get_stream::<MinidumpModuleList>()?;
+    let report = version_check::check_module_versions(&modules, &expected);
+    Ok(serde_wasm_bindgen::to_value(&report)?)
+}
+
+// code_file, code_id, debug_file and debug_id of every module, for fetching symbols before a
+// full parse. Only the module list is read.
+#[wasm_bindgen]
+pub fn list_modules_for_symbols(bytes: &[u8]) -> std::result::Result<JsValue, JsValue> {
+    list_modules_for_symbols_internal(bytes).map_err(Into::into)
+}
+
+fn list_modules_for_symbols_internal(bytes: &[u8]) -> Result<JsValue> {
+    let dump = read_minidump(bytes)?;
+    let modules = dump.get_stream::<MinidumpModuleList>()?;
+    let symbol_modules = modules::list_modules_for_symbols(&modules);
+    Ok(serde_wasm_bindgen::to_value(&symbol_modules)?)
+}
+
+// Find threads whose name contains `query`, or whose unwound frames reference a module whose
+// file name contains it (case-insensitive, see find_matching_threads). Returns matching thread
+// ids in `threads_data` order.
+#[wasm_bindgen]
+pub async fn find_threads(bytes: &[u8], query: &str) -> std::result::Result<JsValue, JsValue> {
+    find_threads_internal(bytes, query)
+        .await
+        .map_err(Into::into)
+}
+
+async fn find_threads_internal(bytes: &[u8], query: &str) -> Result<JsValue> {
+    let thread_ids = find_thread_ids(bytes, query).await?;
+    Ok(serde_wasm_bindgen::to_value(&thread_ids)?)
+}
+
+async fn find_thread_ids(bytes: &[u8], query: &str) -> Result<Vec<u32>> {
+    let dump = read_minidump(bytes)?;
+    let streams = extract_minidump_streams(&dump, bytes);
+    let options = ParseOptions::default();
+    let thread_ids = match streams.threads.as_ref() {
+        Some(threads) => {
+            let ctx = ThreadParseContext::new(
+                threads,
+                streams.system.as_ref(),
+                streams.thread_names.as_ref(),
+                streams.modules.as_ref(),
+                &dump,
+                streams.memory.as_ref(),
+                &options,
+            );
+            let threads_data = parse_threads_data_async(&ctx, None).await;
+            find_matching_threads(&threads_data, query)
+        }
+        None => Vec::new(),
+    };
+    Ok(thread_ids)
+}
+
+// A human-readable Markdown report (OS and crash reason, crashing thread stack, modules)
+// that can be downloaded and shared
+#[wasm_bindgen]
+pub async fn report_markdown(bytes: &[u8]) -> std::result::Result<String, JsValue> {
+    report_markdown_internal(bytes).await.map_err(Into::into)
+}
+
+async fn report_markdown_internal(bytes: &[u8]) -> Result<String> {
+    let dump = read_minidump(bytes)?;
+    let streams = extract_minidump_streams(&dump, bytes);
+    let overview = build_overview(&streams, &dump, &ParseOptions::default()).await?;
+    Ok(report::build_report_markdown(
+        overview.system_info.as_ref(),
+        overview.exception_info.as_ref(),
+        overview.crash_summary.as_ref(),
+        overview.threads_data.as_deref(),
+        overview.modules_data.as_ref(),
+    ))
+}
+
+// Fully unwind and symbolicate one thread, for threads left unsymbolicated by
+// `symbolicate_crashing_thread_only`. Errors if the thread isn't in the dump.
+#[wasm_bindgen]
+pub async fn unwind_thread(bytes: &[u8], thread_id: u32) -> std::result::Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    unwind_thread_internal(bytes, thread_id)
+        .await
+        .map_err(Into::into)
+}
+
+async fn unwind_thread_internal(bytes: &[u8], thread_id: u32) -> Result<JsValue> {
+    let thread_data = unwind_thread_data(bytes, thread_id).await?;
+    Ok(serde_wasm_bindgen::to_value(&thread_data)?)
+}
+
+async fn unwind_thread_data(bytes: &[u8], thread_id: u32) -> Result<ThreadData> {
+    let dump = read_minidump(bytes)?;
+    let streams = extract_minidump_streams(&dump, bytes);
+    let threads = streams
+        .threads
+        .as_ref()
+        .ok_or_else(|| ViewerError::NotFound(format!("thread {}", thread_id)))?;
+    let thread = threads
+        .get_thread(thread_id)
+        .ok_or_else(|| ViewerError::NotFound(format!("thread {}", thread_id)))?;
+
+    let options = ParseOptions::default();
+    let ctx = ThreadParseContext::new(
+        threads,
+        streams.system.as_ref(),
+        streams.thread_names.as_ref(),
+        streams.modules.as_ref(),
+        &dump,
+        streams.memory.as_ref(),
+        &options,
+    )
+    .with_crashing_thread(streams.exception.as_ref().map(|e| e.raw.thread_id));
+    Ok(parse_thread_data_async(thread, &ctx).await)
+}
+
+// Frames `[start, start + count)` of `thread_id`'s stack plus the total frame count, for
+// paging through very deep stacks. Nothing is cached: every call unwinds the thread again.
+#[wasm_bindgen]
+pub async fn thread_frames_page(
+    bytes: &[u8],
+    thread_id: u32,
+    start: u32,
+    count: u32,
+) -> std::result::Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    thread_frames_page_internal(bytes, thread_id, start, count)
+        .await
+        .map_err(Into::into)
+}
+
+async fn thread_frames_page_internal(
+    bytes: &[u8],
+    thread_id: u32,
+    start: u32,
+    count: u32,
+) -> Result<JsValue> {
+    let frames = unwind_thread_data(bytes, thread_id)
+        .await?
+        .stack_frames
+        .unwrap_or_default();
+    let page = StackFramesPage {
+        total: frames.len() as u32,
+        start,
+        frames: frames
+            .into_iter()
+            .skip(start as usize)
+            .take(count as usize)
+            .collect(),
+    };
+    Ok(serde_wasm_bindgen::to_value(&page)?)
+}
+
+// Captured memory inside the image range of the module named `module_name` (full path or file
+// name, case-insensitive), e.g. to inspect patched code or globals. Each region's bytes are
+// capped at 1 MiB. Errors if no module has that name.
+#[wasm_bindgen]
+pub fn module_memory(bytes: &[u8], module_name: &str) -> std::result::Result<JsValue, JsValue> {
+    module_memory_internal(bytes, module_name).map_err(Into::into)
+}
+
+fn module_memory_internal(bytes: &[u8], module_name: &str) -> Result<JsValue> {
+    let dump = read_minidump(bytes)?;
+    let modules = dump.get_stream::<MinidumpModuleList>()?;
+    let module = ModuleIndex::new(&modules)
+        .module_named(module_name)
+        .ok_or_else(|| ViewerError::NotFound(format!("module {}", module_name)))?;
+    let base = module.raw.base_of_image;
+    let end = base.saturating_add(module.raw.size_of_image as u64);
+    let memory = load_memory(&dump, bytes).unwrap_or_default();
+    Ok(serde_wasm_bindgen::to_value(&regions_in_range(
+        &memory, base, end,
+    ))?)
+}
+
+// Up to `length` captured bytes starting at `address` (capped at 1 MiB), fewer if the
+// captured region ends first. When nothing is captured there the error says whether the dump
+// type excludes such memory or just this address, see missing_memory_error.
+#[wasm_bindgen]
+pub fn read_memory(
+    bytes: &[u8],
+    address: u64,
+    length: u32,
+) -> std::result::Result<JsValue, JsValue> {
+    read_memory_internal(bytes, address, length).map_err(Into::into)
+}
+
+fn read_memory_internal(bytes: &[u8], address: u64, length: u32) -> Result<JsValue> {
+    let data = read_memory_data(bytes, address, length)?;
+    Ok(serde_wasm_bindgen::to_value(&data)?)
+}
+
+fn read_memory_data(bytes: &[u8], address: u64, length: u32) -> Result<Vec<u8>> {
+    let dump = read_minidump(bytes)?;
+    let memory = load_memory(&dump, bytes).unwrap_or_default();
+    let length = (length as usize).min(MAX_RANGE_REGION_BYTES);
+    let data = memory_at(&memory, address, length)
+        .ok_or_else(|| missing_memory_error(&dump, &memory, address))?;
+    Ok(data.to_vec())
+}
+
+// "What is this address?": the module, memory region, nearest symbol and thread stack that
+// contain `address`. An address the dump knows nothing about yields all-None fields.
+#[wasm_bindgen]
+pub async fn describe_address(bytes: &[u8], address: u64) -> std::result::Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    describe_address_internal(bytes, address)
+        .await
+        .map_err(Into::into)
+}
+
+async fn describe_address_internal(bytes: &[u8], address: u64) -> Result<JsValue> {
+    let dump = read_minidump(bytes)?;
+    let streams = extract_minidump_streams(&dump, bytes);
+    let sources = AddressSources {
+        system: streams.system.as_ref(),
+        modules: streams.modules.as_ref(),
+        threads: streams.threads.as_ref(),
+        memory: streams.memory.as_ref(),
+        memory_info: streams.memory_info.as_ref(),
+    };
+    let description = build_address_description(address, &sources).await;
+    Ok(serde_wasm_bindgen::to_value(&description)?)
+}
+
+// The captured stack of `thread_id`: base address, declared size and raw bytes (capped at
+// 1 MiB). Errors if the thread isn't in the dump or its stack wasn't captured.
+#[wasm_bindgen]
+pub fn thread_stack(bytes: &[u8], thread_id: u32) -> std::result::Result<JsValue, JsValue> {
+    thread_stack_internal(bytes, thread_id).map_err(Into::into)
+}
+
+fn thread_stack_internal(bytes: &[u8], thread_id: u32) -> Result<JsValue> {
+    let stack = read_thread_stack_data(bytes, thread_id)?;
+    Ok(serde_wasm_bindgen::to_value(&stack)?)
+}
+
+fn read_thread_stack_data(bytes: &[u8], thread_id: u32) -> Result<ThreadStack> {
+    let dump = read_minidump(bytes)?;
+    let threads = dump.get_stream::<MinidumpThreadList>()?;
+    let thread = threads
+        .get_thread(thread_id)
+        .ok_or_else(|| ViewerError::NotFound(format!("thread {}", thread_id)))?;
+    let memory = load_memory(&dump, bytes).unwrap_or_default();
+    read_thread_stack(thread, &memory)
+        .ok_or_else(|| ViewerError::NotFound(format!("stack memory for thread {}", thread_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::{
+        DumpBuilder, Exception, MemoryInfo, Module, PLATFORM_WIN32_NT,
+        PROCESSOR_ARCHITECTURE_AMD64, SystemInfo, Thread, amd64_context, block_on,
+    };
+
+    const MODULE_BASE: u64 = 0x1_4000_0000;
+    const STACK_BASE: u64 = 0x10_0000;
+    const CRASH_IP: u64 = MODULE_BASE + 0x1234;
+
+    // System info, one thread, one module and an access violation on that thread
+    fn minimal_dump() -> Vec<u8> {
+        let context = amd64_context(CRASH_IP, STACK_BASE + 0x100, STACK_BASE + 0x200);
+        let mut exception = Exception::new(0x10, 0xC000_0005, CRASH_IP, context.clone());
+        exception.parameters = vec![1, 0x8]; // Write to 0x8
+        DumpBuilder::new()
+            .system_info(SystemInfo::new(
+                PROCESSOR_ARCHITECTURE_AMD64,
+                PLATFORM_WIN32_NT,
+            ))
+            .thread(Thread::new(0x10, context, STACK_BASE, vec![0; 0x1000]))
+            .module(Module::new(MODULE_BASE, 0x10000, "C:\\app\\app.exe"))
+            .exception(exception)
+            .build()
+    }
+
+    #[test]
+    fn parses_minimal_dump() {
+        let overview = block_on(parse_overview(&minimal_dump(), &ParseOptions::default())).unwrap();
+
+        assert!(!overview.is_empty);
+        assert_eq!(overview.dump_completeness, "mini");
+        let system = overview.system_info.unwrap();
+        assert_eq!(system.os.as_deref(), Some("Windows"));
+        assert!(!overview.system_info_inferred);
+
+        assert_eq!(overview.modules_count, Some(1));
+        let modules = overview.modules_data.unwrap().modules;
+        assert_eq!(modules[0].name, "C:\\app\\app.exe");
+        assert_eq!(modules[0].normalized_name, "app.exe");
+        assert_eq!(modules[0].size_of_image, 0x10000);
+
+        let exception = overview.exception_info.unwrap();
+        assert_eq!(exception.thread_id, 0x10);
+        assert_eq!(
+            exception.crash_reason.as_deref(),
+            Some("EXCEPTION_ACCESS_VIOLATION_WRITE")
+        );
+        assert_eq!(exception.crash_address.unwrap().raw_value(), 0x8);
+        assert_eq!(
+            exception.faulting_instruction.unwrap().raw_value(),
+            CRASH_IP
+        );
+
+        assert_eq!(overview.threads_count, Some(1));
+        let thread = &overview.threads_data.as_ref().unwrap()[0];
+        assert_eq!(thread.thread_id, 0x10);
+        assert!(thread.is_crashing);
+        assert!(thread.stack_pointer_in_bounds);
+        let stack = thread.stack.as_ref().unwrap();
+        assert_eq!(stack.start_address.raw_value(), STACK_BASE);
+        assert_eq!(stack.stack_captured_bytes, 0x1000);
+        let frame = &thread.stack_frames.as_ref().unwrap()[0];
+        assert_eq!(frame.instruction_address.raw_value(), CRASH_IP);
+        assert_eq!(frame.trust_level, "context");
+        assert_eq!(frame.module_name.as_deref(), Some("C:\\app\\app.exe"));
+    }
+
+    #[test]
+    fn overview_json_is_deterministic() {
+        // Several threads, modules and memory info entries with multi-flag protections, the
+        // parts of the output that used to come out in hash order
+        let mut builder = DumpBuilder::new()
+            .system_info(SystemInfo::new(
+                PROCESSOR_ARCHITECTURE_AMD64,
+                PLATFORM_WIN32_NT,
+            ))
+            .module(Module::new(MODULE_BASE, 0x10000, "C:\\app\\app.exe"))
+            .module(Module::new(
+                0x7ff8_0000_0000,
+                0x20000,
+                "C:\\Windows\\ntdll.dll",
+            ));
+        for index in 0..4u64 {
+            let stack = STACK_BASE + index * 0x10000;
+            let context = amd64_context(CRASH_IP + index, stack + 0x100, stack + 0x200);
+            builder = builder
+                .thread(Thread::new(
+                    0x10 + index as u32,
+                    context,
+                    stack,
+                    vec![0; 0x1000],
+                ))
+                .memory_info(MemoryInfo {
+                    base: stack,
+                    allocation_base: stack,
+                    allocation_protect: 0x04,
+                    size: 0x1000,
+                    state: 0x1000,
+                    protect: 0x104, // PAGE_READWRITE | PAGE_GUARD
+                    kind: 0x20000,
+                });
+        }
+        let bytes = builder.build();
+
+        let first = block_on(overview_json_internal(&bytes)).unwrap();
+        let second = block_on(overview_json_internal(&bytes)).unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn reads_memory64_only_dump_that_get_memory_rejects() {
+        let context = amd64_context(CRASH_IP, STACK_BASE + 0x100, STACK_BASE + 0x200);
+        let mut bytes = DumpBuilder::new()
+            .system_info(SystemInfo::new(
+                PROCESSOR_ARCHITECTURE_AMD64,
+                PLATFORM_WIN32_NT,
+            ))
+            .thread(Thread::new(0x10, context, STACK_BASE, vec![0xab; 0x1000]))
+            .module(Module::new(MODULE_BASE, 0x10000, "C:\\app\\app.exe"))
+            .memory(0x20_0000, vec![0; 0x2000])
+            .memory64()
+            .build();
+        // Cut the last (heap) range short, as in a full dump whose write was interrupted
+        bytes.truncate(bytes.len() - 0x100);
+        assert!(
+            Minidump::read(bytes.as_slice())
+                .unwrap()
+                .get_memory()
+                .is_none()
+        );
+
+        let overview = block_on(parse_overview(&bytes, &ParseOptions::default())).unwrap();
+        assert_eq!(overview.dump_completeness, "full");
+        let thread = &overview.threads_data.as_ref().unwrap()[0];
+        assert!(!matches!(
+            thread.stack_unwinding_method,
+            threads::StackUnwindingMethod::Failed
+        ));
+        let frame = &thread.stack_frames.as_ref().unwrap()[0];
+        assert_eq!(frame.instruction_address.raw_value(), CRASH_IP);
+
+        let stack = read_thread_stack_data(&bytes, 0x10).unwrap();
+        assert_eq!(stack.base_address.raw_value(), STACK_BASE);
+        assert_eq!(stack.bytes, vec![0xab; 0x1000]);
+    }
+
+    #[test]
+    fn finds_threads_by_normalized_module_name() {
+        let bytes = minimal_dump();
+        for query in ["app.exe", "APP", "D:\\elsewhere\\App.EXE"] {
+            assert_eq!(
+                block_on(find_thread_ids(&bytes, query)).unwrap(),
+                vec![0x10]
+            );
+        }
+        // Only the file name counts, not the directory it shares with the module
+        let query = "C:\\app\\other.dll";
+        assert!(block_on(find_thread_ids(&bytes, query)).unwrap().is_empty());
+    }
+
+    impl SectionSink for Vec<(String, serde_json::Value)> {
+        fn emit<T: Serialize>(&mut self, name: &str, value: &T) -> Result<()> {
+            self.push((name.to_string(), serde_json::to_value(value)?));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn streaming_honors_options_and_sends_derived_sections() {
+        let bytes = minimal_dump();
+        let options = ParseOptions {
+            frame_display: true,
+            ..ParseOptions::default()
+        };
+        let mut sections = Vec::new();
+        block_on(parse_minidump_streaming_internal(
+            &bytes,
+            &mut sections,
+            &options,
+        ))
+        .unwrap();
+        let section = |name: &str| {
+            sections
+                .iter()
+                .find(|(section, _)| section == name)
+                .map(|(_, value)| value.clone())
+                .unwrap_or_else(|| panic!("no {} section", name))
+        };
+
+        let thread = section("thread");
+        assert_eq!(thread["stack_frames"][0]["display"], "#0 app.exe+0x1234");
+
+        let overview = block_on(parse_overview(&bytes, &options)).unwrap();
+        let overview = serde_json::to_value(&overview).unwrap();
+        assert!(overview["crash_summary"].is_object());
+        for name in [
+            "crash_summary",
+            "capture_reason",
+            "crash_signature",
+            "crash_signature_hash",
+            "crash_location",
+            "priority_inversion_candidates",
+        ] {
+            assert_eq!(section(name), overview[name], "{}", name);
+        }
+    }
+
+    #[test]
+    fn reads_captured_memory() {
+        let data = read_memory_data(&minimal_dump(), STACK_BASE + 0xff0, 0x100).unwrap();
+        assert_eq!(data, vec![0; 0x10]); // Up to the end of the stack region
+    }
+
+    #[test]
+    fn uncaptured_memory_in_stacks_only_dump_was_never_captured() {
+        let error = read_memory_data(&minimal_dump(), 0x8, 4).unwrap_err();
+        assert!(matches!(error, ViewerError::MemoryNotCaptured(_)));
+        assert_eq!(
+            error.to_string(),
+            "memory not captured: 0x8: this dump is stacks-only (MINIDUMP_TYPE 0x0); heap \
+             memory was never captured"
+        );
+    }
+
+    #[test]
+    fn ignores_the_dump_type_of_breakpad_and_crashpad_dumps() {
+        // Both write MINIDUMP_TYPE 0 even when they capture more than the stacks
+        for stream_type in [0x4767_0001, 0x4350_0001] {
+            let context = amd64_context(CRASH_IP, STACK_BASE + 0x100, STACK_BASE + 0x200);
+            let bytes = DumpBuilder::new()
+                .thread(Thread::new(0x10, context, STACK_BASE, vec![0; 0x1000]))
+                .memory(0x50_0000, vec![0xaa; 0x1000])
+                .stream(stream_type, vec![0; 12])
+                .build();
+            let error = read_memory_data(&bytes, 0xdead_0000, 4).unwrap_err();
+            assert!(
+                matches!(error, ViewerError::AddressNotCaptured(_)),
+                "{:#x}",
+                stream_type
+            );
+        }
+    }
+
+    #[test]
+    fn address_outside_full_memory_dump_regions() {
+        let context = amd64_context(CRASH_IP, STACK_BASE + 0x100, STACK_BASE + 0x200);
+        let bytes = DumpBuilder::new()
+            .flags(0x0002) // MiniDumpWithFullMemory
+            .system_info(SystemInfo::new(
+                PROCESSOR_ARCHITECTURE_AMD64,
+                PLATFORM_WIN32_NT,
+            ))
+            .thread(Thread::new(0x10, context, STACK_BASE, vec![0; 0x1000]))
+            .memory(0x50_0000, vec![0xaa; 0x1000])
+            .memory64()
+            .build();
+        assert_eq!(
+            read_memory_data(&bytes, 0x50_0800, 2).unwrap(),
+            [0xaa, 0xaa]
+        );
+        let error = read_memory_data(&bytes, 0xdead_0000, 4).unwrap_err();
+        assert!(matches!(error, ViewerError::AddressNotCaptured(_)));
+        assert!(
+            error
+                .to_string()
+                .ends_with("0xdead0000 is outside all captured memory regions")
+        );
+    }
+
+    fn streams_present(bytes: &[u8]) -> Vec<(String, u32, bool)> {
+        let overview = block_on(parse_overview(bytes, &ParseOptions::default())).unwrap();
+        overview
+            .streams_present
+            .into_iter()
+            .map(|stream| (stream.name, stream.stream_type, stream.parsed))
+            .collect()
+    }
+
+    #[test]
+    fn lists_present_streams_with_type_and_parse_status() {
+        let expected = [
+            ("SystemInfo", 7, true),
+            ("Exception", 6, true),
+            ("ThreadList", 3, true),
+            ("ModuleList", 4, true),
+            ("MemoryList", 5, true),
+        ]
+        .map(|(name, stream_type, parsed)| (name.to_string(), stream_type, parsed));
+        assert_eq!(streams_present(&minimal_dump()), expected);
+
+        // A truncated exception stream is listed unparsed; absent streams aren't listed, and
+        // a Memory64 list is reported under its own stream type
+        let context = amd64_context(CRASH_IP, STACK_BASE + 0x100, STACK_BASE + 0x200);
+        let bytes = DumpBuilder::new()
+            .thread(Thread::new(0x10, context, STACK_BASE, vec![0; 0x1000]))
+            .stream(6, vec![0; 8]) // ExceptionStream
+            .memory64()
+            .build();
+        let expected = [
+            ("Exception", 6, false),
+            ("ThreadList", 3, true),
+            ("MemoryList", 9, true),
+        ]
+        .map(|(name, stream_type, parsed)| (name.to_string(), stream_type, parsed));
+        assert_eq!(streams_present(&bytes), expected);
+    }
+
+    #[test]
+    fn rejects_input_shorter_than_a_header() {
+        let header_only = DumpBuilder::new().build();
+        assert_eq!(header_only.len(), MINIDUMP_HEADER_SIZE);
+        for bytes in [&[][..], &header_only[..MINIDUMP_HEADER_SIZE - 1]] {
+            let error = read_minidump(bytes).err().unwrap();
+            assert_eq!(
+                error.to_string(),
+                "minidump read error: input too small to be a minidump"
+            );
+        }
+        let dump = read_minidump(&header_only).unwrap();
+        assert_eq!(dump.header.stream_count, 0);
+    }
+
+    #[test]
+    fn rejects_dump_without_signature() {
+        let mut bytes = minimal_dump();
+        bytes[0..4].copy_from_slice(b"XXXX");
+        assert!(block_on(parse_overview(&bytes, &ParseOptions::default())).is_err());
+    }
+}
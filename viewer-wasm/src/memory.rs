@@ -1,7 +1,12 @@
-use crate::common::{SafeU64, debug_output};
-use minidump::{MinidumpMemoryInfoList, UnifiedMemoryList};
+use crate::common::{SafeU64, debug_output, read_u32_le, read_u64_le};
+use crate::errors::ViewerError;
+use crate::options::{ParseOptions, SizeUnits};
+use minidump::format::MINIDUMP_MEMORY_DESCRIPTOR64;
+use minidump::{
+    Endian, Minidump, MinidumpMemory64, MinidumpMemory64List, MinidumpMemoryInfoList,
+    MinidumpStream, MinidumpThreadList, MinidumpThreadNames, UnifiedMemoryList,
+};
 use serde::Serialize;
-use std::collections::HashMap;
 use std::sync::OnceLock;
 
 #[derive(Serialize)]
@@ -10,8 +15,10 @@ pub struct MemoryData {
     pub regions_count: usize,
     pub memory_info: Option<MemoryRangeMap>,
     pub has_memory_info_stream: bool,
-    pub total_memory_size: u64,
+    pub total_memory_size: u64, // Sum of declared region sizes
     pub total_memory_size_formatted: String,
+    pub total_captured_size: u64, // Sum of the bytes actually present in the dump
+    pub total_captured_size_formatted: String,
     pub debug: Option<String>,
 }
 
@@ -24,12 +31,35 @@ pub struct MemoryRegion {
     pub has_data: bool,
     pub data_size: usize,
     pub address_range: String,
+    pub entropy: Option<f32>, // Shannon entropy in bits per byte (0.0 - 8.0), when requested
+    pub stack_owner: Option<StackOwner>, // The thread whose stack this region holds
+}
+
+#[derive(Serialize)]
+pub struct StackOwner {
+    pub thread_id: u32,
+    pub thread_name: Option<String>,
 }
 
 #[derive(Serialize)]
 pub struct MemoryRangeMap {
     pub ranges: Vec<MemoryInfoRange>,
     pub ranges_count: usize,
+    pub list_header: Option<MemoryInfoListHeader>,
+    // Bases of committed ranges that are writable and executable at once, where JIT output and
+    // injected or self-modifying code live
+    pub rwx_regions: Vec<SafeU64>,
+}
+
+#[derive(Serialize)]
+pub struct MemoryInfoListHeader {
+    // MINIDUMP_MEMORY_INFO_LIST fields
+    pub size_of_header: u32,
+    pub size_of_entry: u32,
+    pub number_of_entries: u64,
+    // Whether entries have the MINIDUMP_MEMORY_INFO size we know; larger entries come from
+    // newer writers and are read using only their known leading fields
+    pub entry_size_matches: bool,
 }
 
 #[derive(Serialize)]
@@ -44,12 +74,33 @@ pub struct MemoryInfoRange {
     pub protection_value: u32,
     pub allocation_protection: String,
     pub allocation_protection_value: u32,
+    // Committed pages whose protection differs from the allocation's, i.e. re-protected
+    pub protection_changed: bool,
+    pub protection_note: Option<String>,
     pub memory_type: String,
     pub memory_type_value: u32,
 }
 
-pub fn parse_memory_data(memory: &UnifiedMemoryList) -> MemoryData {
+pub fn parse_memory_data(
+    memory: &UnifiedMemoryList,
+    threads: Option<&MinidumpThreadList>,
+    thread_names: Option<&MinidumpThreadNames>,
+    options: &ParseOptions,
+) -> MemoryData {
+    // (stack start, thread id) of every thread, to label the regions holding their stacks
+    let stacks: Vec<(u64, u32)> = threads
+        .map(|threads| {
+            threads
+                .threads
+                .iter()
+                .filter(|thread| thread.raw.stack.start_of_memory_range != 0)
+                .map(|thread| (thread.raw.stack.start_of_memory_range, thread.raw.thread_id))
+                .collect()
+        })
+        .unwrap_or_default();
     let mut regions = Vec::new();
+    let mut total_memory_size = 0u64;
+    let mut total_captured_size = 0u64;
 
     // Parse memory regions from the memory list
     for memory_region in memory.iter() {
@@ -59,11 +110,27 @@ pub fn parse_memory_data(memory: &UnifiedMemoryList) -> MemoryData {
         let bytes = memory_region.bytes();
         let data_size = bytes.len();
         let has_data = !bytes.is_empty();
+        total_memory_size = total_memory_size.saturating_add(size);
+        total_captured_size = total_captured_size.saturating_add(data_size as u64);
 
         let start_address = start_addr.into();
         let end_address = end_addr.into();
-        let size_formatted = format_memory_size(size);
+        let size_formatted = format_memory_size(size, options.size_units);
         let address_range = format!("{:#x} - {:#x}", start_addr, end_addr);
+        let entropy = if options.compute_entropy && has_data {
+            Some(shannon_entropy(bytes))
+        } else {
+            None
+        };
+        let stack_owner = stacks
+            .iter()
+            .find(|&&(stack_start, _)| (start_addr..end_addr).contains(&stack_start))
+            .map(|&(_, thread_id)| StackOwner {
+                thread_id,
+                thread_name: thread_names
+                    .and_then(|names| names.get_name(thread_id))
+                    .map(|name| name.into_owned()),
+            });
 
         regions.push(MemoryRegion {
             start_address,
@@ -73,18 +140,19 @@ pub fn parse_memory_data(memory: &UnifiedMemoryList) -> MemoryData {
             has_data,
             data_size,
             address_range,
+            entropy,
+            stack_owner,
         });
     }
 
-    // Sort memory regions by start address to ensure consistent ordering
+    // Sort memory regions by start address (then size) to ensure consistent ordering
     // from lowest to highest address
-    regions.sort_by_key(|region| region.start_address.raw_value());
+    regions.sort_by_key(|region| (region.start_address.raw_value(), region.size));
 
     let regions_count = regions.len();
 
-    // Calculate total memory size
-    let total_memory_size: u64 = regions.iter().map(|r| r.size).sum();
-    let total_memory_size_formatted = format_memory_size(total_memory_size);
+    let total_memory_size_formatted = format_memory_size(total_memory_size, options.size_units);
+    let total_captured_size_formatted = format_memory_size(total_captured_size, options.size_units);
 
     MemoryData {
         regions,
@@ -93,69 +161,356 @@ pub fn parse_memory_data(memory: &UnifiedMemoryList) -> MemoryData {
         has_memory_info_stream: false, // Will be set when memory info is available
         total_memory_size,
         total_memory_size_formatted,
+        total_captured_size,
+        total_captured_size_formatted,
         debug: debug_output(memory),
     }
 }
 
-pub fn parse_memory_info_data(memory_info: &MinidumpMemoryInfoList) -> MemoryRangeMap {
+pub fn parse_memory_info_data(
+    memory_info: &MinidumpMemoryInfoList,
+    options: &ParseOptions,
+) -> MemoryRangeMap {
     let mut ranges = Vec::new();
 
     for info in memory_info.iter() {
-        let base_address = info.raw.base_address.into();
-        let allocation_base = info.raw.allocation_base.into();
-        let region_size = info.raw.region_size;
-        let region_size_formatted = format_memory_size(region_size);
-
-        // Parse memory state - convert enum to u32
-        let state_value = info.state.bits();
-        let (state, _) = parse_memory_state(state_value);
-
-        // Parse memory protection - convert enum to u32
-        let protection_value = info.protection.bits();
-        let (protection, _) = parse_memory_protection(protection_value);
-        let allocation_protection_value = info.allocation_protection.bits();
-        let (allocation_protection, _) = parse_memory_protection(allocation_protection_value);
-
-        // Parse memory type - convert enum to u32
-        let memory_type_value = info.ty.bits();
-        let (memory_type, _) = parse_memory_type(memory_type_value);
-
-        ranges.push(MemoryInfoRange {
-            base_address,
-            allocation_base,
-            region_size,
-            region_size_formatted,
-            state,
-            state_value,
-            protection,
-            protection_value,
-            allocation_protection,
-            allocation_protection_value,
-            memory_type,
-            memory_type_value,
-        });
+        let fields = MemoryInfoFields {
+            base_address: info.raw.base_address,
+            allocation_base: info.raw.allocation_base,
+            region_size: info.raw.region_size,
+            state: info.state.bits(),
+            protection: info.protection.bits(),
+            allocation_protection: info.allocation_protection.bits(),
+            memory_type: info.ty.bits(),
+        };
+        ranges.push(build_memory_info_range(fields, options.size_units));
     }
 
-    // Sort memory info ranges by base address to ensure consistent ordering
+    // Sort memory info ranges by base address (then size) to ensure consistent ordering
     // from lowest to highest address
-    ranges.sort_by_key(|range| range.base_address.raw_value());
+    ranges.sort_by_key(|range| (range.base_address.raw_value(), range.region_size));
 
     let ranges_count = ranges.len();
 
     MemoryRangeMap {
+        rwx_regions: rwx_regions(&ranges),
         ranges,
         ranges_count,
+        list_header: None, // Will be populated from the raw stream when available
+    }
+}
+
+// Size of MINIDUMP_MEMORY_INFO, the only entry layout the minidump crate accepts
+const MEMORY_INFO_ENTRY_SIZE: u32 = 48;
+
+pub fn parse_memory_info_list_header(bytes: &[u8]) -> Option<MemoryInfoListHeader> {
+    let size_of_entry = read_u32_le(bytes, 4)?;
+    Some(MemoryInfoListHeader {
+        size_of_header: read_u32_le(bytes, 0)?,
+        size_of_entry,
+        number_of_entries: read_u64_le(bytes, 8)?,
+        entry_size_matches: size_of_entry == MEMORY_INFO_ENTRY_SIZE,
+    })
+}
+
+// Fallback for memory info lists the minidump crate rejects because their entries are larger
+// than MINIDUMP_MEMORY_INFO. Each entry is read using the known leading fields and then
+// skipped by `size_of_entry`. Entries smaller than the known layout can't be decoded.
+pub fn parse_memory_info_raw(bytes: &[u8], options: &ParseOptions) -> Option<MemoryRangeMap> {
+    let header = parse_memory_info_list_header(bytes)?;
+    if header.size_of_entry < MEMORY_INFO_ENTRY_SIZE {
+        return None;
+    }
+
+    let entry_size = header.size_of_entry as usize;
+    let mut ranges = Vec::new();
+    let mut offset = header.size_of_header as usize;
+
+    for _ in 0..header.number_of_entries {
+        let Some(entry) = offset
+            .checked_add(entry_size)
+            .and_then(|end| bytes.get(offset..end))
+        else {
+            break;
+        };
+
+        // base_address, allocation_base, allocation_protection, (pad), region_size,
+        // state, protection, type, (pad)
+        let fields = MemoryInfoFields {
+            base_address: read_u64_le(entry, 0)?,
+            allocation_base: read_u64_le(entry, 8)?,
+            region_size: read_u64_le(entry, 24)?,
+            state: read_u32_le(entry, 32)?,
+            protection: read_u32_le(entry, 36)?,
+            allocation_protection: read_u32_le(entry, 16)?,
+            memory_type: read_u32_le(entry, 40)?,
+        };
+        ranges.push(build_memory_info_range(fields, options.size_units));
+        offset += entry_size;
     }
+
+    ranges.sort_by_key(|range| (range.base_address.raw_value(), range.region_size));
+    let ranges_count = ranges.len();
+
+    Some(MemoryRangeMap {
+        rwx_regions: rwx_regions(&ranges),
+        ranges,
+        ranges_count,
+        list_header: Some(header),
+    })
 }
 
-// Helper function to format memory size in human readable format
-fn format_memory_size(bytes: u64) -> String {
+// The MINIDUMP_MEMORY_INFO fields we decode, however they were read
+struct MemoryInfoFields {
+    base_address: u64,
+    allocation_base: u64,
+    region_size: u64,
+    state: u32,
+    protection: u32,
+    allocation_protection: u32,
+    memory_type: u32,
+}
+
+fn build_memory_info_range(fields: MemoryInfoFields, units: SizeUnits) -> MemoryInfoRange {
+    let MemoryInfoFields {
+        base_address,
+        allocation_base,
+        region_size,
+        state: state_value,
+        protection: protection_value,
+        allocation_protection: allocation_protection_value,
+        memory_type: memory_type_value,
+    } = fields;
+    let region_size_formatted = format_memory_size(region_size, units);
+
+    // Parse memory state
+    let (state, _) = parse_memory_state(state_value);
+
+    // Parse memory protection
+    let (protection, _) = parse_memory_protection(protection_value);
+    let (allocation_protection, _) = parse_memory_protection(allocation_protection_value);
+
+    // Parse memory type
+    let (memory_type, _) = parse_memory_type(memory_type_value);
+
+    // Reserved and free pages don't carry a meaningful current protection
+    let protection_changed = state_value == MEM_COMMIT
+        && allocation_protection_value != 0
+        && protection_value != allocation_protection_value;
+    let protection_note = protection_changed.then(|| {
+        let mut note = format!(
+            "re-protected from {} to {}",
+            allocation_protection, protection
+        );
+        if is_executable(protection_value) && !is_executable(allocation_protection_value) {
+            note.push_str(", made executable after allocation (e.g. JIT code)");
+        }
+        note
+    });
+
+    MemoryInfoRange {
+        base_address: base_address.into(),
+        allocation_base: allocation_base.into(),
+        region_size,
+        region_size_formatted,
+        state,
+        state_value,
+        protection,
+        protection_value,
+        allocation_protection,
+        allocation_protection_value,
+        protection_changed,
+        protection_note,
+        memory_type,
+        memory_type_value,
+    }
+}
+
+pub(crate) const MEM_COMMIT: u32 = 0x1000;
+
+// PAGE_EXECUTE, PAGE_EXECUTE_READ, PAGE_EXECUTE_READWRITE or PAGE_EXECUTE_WRITECOPY
+pub(crate) fn is_executable(protection: u32) -> bool {
+    protection & 0xF0 != 0
+}
+
+// PAGE_EXECUTE_READWRITE or PAGE_EXECUTE_WRITECOPY
+const PAGE_EXECUTE_WRITABLE: u32 = 0x40 | 0x80;
+
+fn rwx_regions(ranges: &[MemoryInfoRange]) -> Vec<SafeU64> {
+    ranges
+        .iter()
+        .filter(|range| {
+            range.state_value == MEM_COMMIT && range.protection_value & PAGE_EXECUTE_WRITABLE != 0
+        })
+        .map(|range| range.base_address.raw_value().into())
+        .collect()
+}
+
+// MINIDUMP_MEMORY64_LIST header (NumberOfMemoryRanges, BaseRva) and descriptor sizes
+const MEMORY64_LIST_HEADER_SIZE: usize = 16;
+const MEMORY64_DESCRIPTOR_SIZE: usize = 16;
+
+// The dump's memory, as `Minidump::get_memory` finds it, or else a lenient read of the
+// Memory64 list. The minidump crate rejects a Memory64 list outright when the stream size is
+// off or the last range is cut short, which drops all memory of a truncated full dump.
+pub fn load_memory<'a>(
+    dump: &'a Minidump<'a, &'a [u8]>,
+    dump_bytes: &'a [u8],
+) -> Option<UnifiedMemoryList<'a>> {
+    dump.get_memory().or_else(|| {
+        let stream = dump
+            .get_raw_stream(MinidumpMemory64List::STREAM_TYPE)
+            .ok()?;
+        read_memory64_list_lenient(stream, dump_bytes).map(UnifiedMemoryList::Memory64)
+    })
+}
+
+// Read the descriptors that fit in the stream and the range data that is in the file,
+// keeping a truncated final range
+fn read_memory64_list_lenient<'a>(
+    stream: &[u8],
+    dump_bytes: &'a [u8],
+) -> Option<MinidumpMemory64List<'a>> {
+    let count = read_u64_le(stream, 0)?;
+    let mut rva = read_u64_le(stream, 8)?;
+
+    let mut regions = Vec::new();
+    let descriptors = stream
+        .get(MEMORY64_LIST_HEADER_SIZE..)?
+        .chunks_exact(MEMORY64_DESCRIPTOR_SIZE)
+        .take(usize::try_from(count).unwrap_or(usize::MAX));
+    for descriptor in descriptors {
+        let desc = MINIDUMP_MEMORY_DESCRIPTOR64 {
+            start_of_memory_range: read_u64_le(descriptor, 0)?,
+            data_size: read_u64_le(descriptor, 8)?,
+        };
+        let start = usize::try_from(rva).ok()?;
+        let end = rva
+            .checked_add(desc.data_size)
+            .and_then(|end| usize::try_from(end).ok())?
+            .min(dump_bytes.len());
+        let Some(bytes) = dump_bytes.get(start..end).filter(|b| !b.is_empty()) else {
+            break;
+        };
+
+        regions.push(MinidumpMemory64 {
+            desc,
+            base_address: desc.start_of_memory_range,
+            size: desc.data_size, // Declared size; `bytes` may be shorter if the file is cut off
+            bytes,
+            endian: Endian::Little,
+        });
+        rva += desc.data_size;
+    }
+
+    (!regions.is_empty()).then(|| MinidumpMemory64List::from_regions(regions))
+}
+
+// Read up to `len` bytes starting at `address` from the captured region containing it.
+// The result is bounded by the end of that region, so it may be shorter than `len`.
+pub fn memory_at<'m>(memory: &'m UnifiedMemoryList, address: u64, len: usize) -> Option<&'m [u8]> {
+    let region = memory.memory_at_address(address)?;
+    let offset = usize::try_from(address - region.base_address()).ok()?;
+    let bytes = region.bytes().get(offset..)?;
+    Some(&bytes[..len.min(bytes.len())])
+}
+
+// MINIDUMP_TYPE bits that capture memory beyond thread stacks and module images:
+// MiniDumpWithFullMemory, MiniDumpWithIndirectlyReferencedMemory and
+// MiniDumpWithPrivateReadWriteMemory
+const MINIDUMP_HEAP_MEMORY_FLAGS: u64 = 0x0002 | 0x0040 | 0x0200;
+
+// Vendor stream type ranges (the high 16 bits) of Breakpad and Crashpad
+const BREAKPAD_STREAM_VENDOR: u32 = 0x4767;
+const CRASHPAD_STREAM_VENDOR: u32 = 0x4350;
+
+// Breakpad and Crashpad always write MINIDUMP_TYPE 0, whatever memory they capture (e.g.
+// indirectly referenced memory), so only the header flags of dumps without any of their
+// streams describe what was captured
+fn has_meaningful_dump_type(dump: &Minidump<&[u8]>) -> bool {
+    !dump.all_streams().any(|stream| {
+        matches!(
+            stream.stream_type >> 16,
+            BREAKPAD_STREAM_VENDOR | CRASHPAD_STREAM_VENDOR
+        )
+    })
+}
+
+// Why `address` can't be read: a Windows-written dump whose MINIDUMP_TYPE (header flags) asks
+// for no memory beyond the stacks, and that has no full-memory (Memory64) list, never
+// captured heap memory at all; otherwise the address is merely outside every captured region.
+pub fn missing_memory_error(
+    dump: &Minidump<&[u8]>,
+    memory: &UnifiedMemoryList,
+    address: u64,
+) -> ViewerError {
+    let header_flags = dump.header.flags;
+    let full_memory = matches!(memory, UnifiedMemoryList::Memory64(_));
+    if header_flags & MINIDUMP_HEAP_MEMORY_FLAGS == 0
+        && !full_memory
+        && has_meaningful_dump_type(dump)
+    {
+        ViewerError::MemoryNotCaptured(format!(
+            "{:#x}: this dump is stacks-only (MINIDUMP_TYPE {:#x}); heap memory was never captured",
+            address, header_flags
+        ))
+    } else {
+        ViewerError::AddressNotCaptured(format!(
+            "{:#x} is outside all captured memory regions",
+            address
+        ))
+    }
+}
+
+// Upper bound on the bytes returned per region by `regions_in_range`
+pub const MAX_RANGE_REGION_BYTES: usize = 1024 * 1024;
+
+#[derive(Serialize)]
+pub struct RangeRegion {
+    pub base_address: SafeU64, // Clipped to the requested range
+    pub size: u64,             // Bytes of the region inside the range
+    pub bytes: Vec<u8>,        // At most MAX_RANGE_REGION_BYTES
+    pub truncated: bool,       // Whether bytes is shorter than size
+}
+
+// The parts of every captured region that overlap [start, end), in address order
+pub fn regions_in_range(memory: &UnifiedMemoryList, start: u64, end: u64) -> Vec<RangeRegion> {
+    let mut regions: Vec<RangeRegion> = memory
+        .iter()
+        .filter_map(|region| {
+            let base = region.base_address().max(start);
+            let region_end = region.base_address().saturating_add(region.size()).min(end);
+            if base >= region_end {
+                return None;
+            }
+            let size = region_end - base;
+            let len = usize::try_from(size)
+                .unwrap_or(usize::MAX)
+                .min(MAX_RANGE_REGION_BYTES);
+            let bytes = memory_at(memory, base, len).unwrap_or_default().to_vec();
+            Some(RangeRegion {
+                base_address: base.into(),
+                size,
+                truncated: (bytes.len() as u64) < size,
+                bytes,
+            })
+        })
+        .collect();
+    regions.sort_by_key(|region| region.base_address.raw_value());
+    regions
+}
+
+// Format a byte count for display. Every formatted size goes through here so the unit
+// choice is applied consistently: IEC (KiB, 1024-based) or SI (KB, 1000-based).
+pub fn format_memory_size(bytes: u64, units: SizeUnits) -> String {
     if bytes == 0 {
         return "0 bytes".to_string();
     }
 
-    let k = 1024u64;
-    let sizes = ["bytes", "KB", "MB", "GB", "TB"];
+    let (k, sizes) = match units {
+        SizeUnits::Iec => (1024u64, ["bytes", "KiB", "MiB", "GiB", "TiB"]),
+        SizeUnits::Si => (1000u64, ["bytes", "KB", "MB", "GB", "TB"]),
+    };
     let i = (bytes as f64).log(k as f64).floor() as usize;
     let i = i.min(sizes.len() - 1);
 
@@ -167,6 +522,31 @@ fn format_memory_size(bytes: u64) -> String {
     }
 }
 
+// Shannon entropy of a byte buffer in bits per byte, computed from a single pass histogram.
+// Low values suggest text/heap data, values close to 8.0 suggest compressed or encrypted data.
+fn shannon_entropy(bytes: &[u8]) -> f32 {
+    if bytes.is_empty() {
+        return 0.0;
+    }
+
+    let mut counts = [0u64; 256];
+    for &byte in bytes {
+        counts[byte as usize] += 1;
+    }
+
+    let total = bytes.len() as f64;
+    let entropy: f64 = counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let probability = count as f64 / total;
+            -probability * probability.log2()
+        })
+        .sum();
+
+    entropy as f32
+}
+
 // Optimized memory state parsing with lookup tables
 static STATE_FLAGS: OnceLock<Vec<(u32, &'static str)>> = OnceLock::new();
 
@@ -202,21 +582,22 @@ fn parse_memory_state(state: u32) -> (String, u32) {
 }
 
 // Optimized memory protection parsing with lookup tables
-static PROTECTION_FLAGS: OnceLock<HashMap<u32, &'static str>> = OnceLock::new();
+static PROTECTION_FLAGS: OnceLock<Vec<(u32, &'static str)>> = OnceLock::new();
 static PROTECTION_MODIFIERS: OnceLock<Vec<(u32, &'static str)>> = OnceLock::new();
 
-fn get_protection_flags() -> &'static HashMap<u32, &'static str> {
+// Kept as an ordered list (not a map) so any iteration over it is deterministic
+fn get_protection_flags() -> &'static Vec<(u32, &'static str)> {
     PROTECTION_FLAGS.get_or_init(|| {
-        let mut map = HashMap::new();
-        map.insert(0x01, "PAGE_NOACCESS");
-        map.insert(0x02, "PAGE_READONLY");
-        map.insert(0x04, "PAGE_READWRITE");
-        map.insert(0x08, "PAGE_WRITECOPY");
-        map.insert(0x10, "PAGE_EXECUTE");
-        map.insert(0x20, "PAGE_EXECUTE_READ");
-        map.insert(0x40, "PAGE_EXECUTE_READWRITE");
-        map.insert(0x80, "PAGE_EXECUTE_WRITECOPY");
-        map
+        vec![
+            (0x01, "PAGE_NOACCESS"),
+            (0x02, "PAGE_READONLY"),
+            (0x04, "PAGE_READWRITE"),
+            (0x08, "PAGE_WRITECOPY"),
+            (0x10, "PAGE_EXECUTE"),
+            (0x20, "PAGE_EXECUTE_READ"),
+            (0x40, "PAGE_EXECUTE_READWRITE"),
+            (0x80, "PAGE_EXECUTE_WRITECOPY"),
+        ]
     })
 }
 
@@ -230,17 +611,24 @@ fn get_protection_modifiers() -> &'static Vec<(u32, &'static str)> {
     })
 }
 
+// PAGE_* protection flags as text, e.g. "PAGE_READWRITE | PAGE_GUARD"
+pub fn protection_name(protection: u32) -> String {
+    parse_memory_protection(protection).0
+}
+
 fn parse_memory_protection(protection: u32) -> (String, u32) {
     if protection == 0 {
         return ("NONE".to_string(), 0);
     }
 
     let mut protection_flags = Vec::new();
-    let protection_lookup = get_protection_flags();
 
     // Check basic protection flags
     let basic_protection = protection & 0xFF;
-    if let Some(&flag_name) = protection_lookup.get(&basic_protection) {
+    if let Some(&(_, flag_name)) = get_protection_flags()
+        .iter()
+        .find(|&&(flag_value, _)| flag_value == basic_protection)
+    {
         protection_flags.push(flag_name.to_string());
     } else {
         protection_flags.push(format!("UNKNOWN(0x{:x})", basic_protection));
@@ -290,3 +678,106 @@ fn parse_memory_type(memory_type: u32) -> (String, u32) {
 
     (type_str, memory_type)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{DumpBuilder, MemoryInfo, block_on, put_u32, put_u64};
+    use crate::{ParseOptions, parse_overview};
+
+    const MEMORY_INFO_LIST_STREAM: u32 = 16;
+    const PAGE_READWRITE: u32 = 0x04;
+    const MEM_PRIVATE: u32 = 0x20000;
+
+    // MINIDUMP_MEMORY_INFO_LIST with `count` entries of `entry_size` bytes, of which only
+    // `entries` are written; fields past the known layout are filled with 0xff
+    fn memory_info_list(entry_size: u32, count: u64, entries: &[(u64, u64)]) -> Vec<u8> {
+        let mut bytes = vec![0u8; 16];
+        put_u32(&mut bytes, 0, 16);
+        put_u32(&mut bytes, 4, entry_size);
+        put_u64(&mut bytes, 8, count);
+        for &(base, size) in entries {
+            let mut entry = vec![0xffu8; entry_size as usize];
+            entry[..48].fill(0);
+            put_u64(&mut entry, 0, base);
+            put_u64(&mut entry, 8, base);
+            put_u32(&mut entry, 16, PAGE_READWRITE);
+            put_u64(&mut entry, 24, size);
+            put_u32(&mut entry, 32, MEM_COMMIT);
+            put_u32(&mut entry, 36, PAGE_READWRITE);
+            put_u32(&mut entry, 40, MEM_PRIVATE);
+            bytes.extend_from_slice(&entry);
+        }
+        bytes
+    }
+
+    #[test]
+    fn reads_entries_larger_than_minidump_memory_info() {
+        let stream = memory_info_list(64, 2, &[(0x20_0000, 0x2000), (0x10_0000, 0x1000)]);
+        let map = parse_memory_info_raw(&stream, &ParseOptions::default()).unwrap();
+
+        let header = map.list_header.unwrap();
+        assert_eq!(header.size_of_header, 16);
+        assert_eq!(header.size_of_entry, 64);
+        assert_eq!(header.number_of_entries, 2);
+        assert!(!header.entry_size_matches);
+
+        assert_eq!(map.ranges_count, 2);
+        let range = &map.ranges[0]; // Sorted by base address
+        assert_eq!(range.base_address.raw_value(), 0x10_0000);
+        assert_eq!(range.region_size, 0x1000);
+        assert_eq!(range.state_value, MEM_COMMIT);
+        assert_eq!(range.protection, "PAGE_READWRITE");
+        assert_eq!(range.allocation_protection_value, PAGE_READWRITE);
+        assert_eq!(range.memory_type_value, MEM_PRIVATE);
+        assert_eq!(map.ranges[1].base_address.raw_value(), 0x20_0000);
+    }
+
+    #[test]
+    fn stops_at_the_end_of_the_stream_and_rejects_small_entries() {
+        let stream = memory_info_list(48, 3, &[(0x10_0000, 0x1000), (0x20_0000, 0x1000)]);
+        let map = parse_memory_info_raw(&stream, &ParseOptions::default()).unwrap();
+        assert_eq!(map.ranges_count, 2);
+        assert!(map.list_header.unwrap().entry_size_matches);
+
+        let stream = memory_info_list(40, 0, &[]);
+        assert!(parse_memory_info_raw(&stream, &ParseOptions::default()).is_none());
+        assert!(parse_memory_info_list_header(&stream[..12]).is_none());
+    }
+
+    #[test]
+    fn overview_falls_back_to_reading_extended_entries() {
+        let options = ParseOptions::default();
+        let extended = DumpBuilder::new()
+            .memory(0x10_0000, vec![0; 0x1000])
+            .stream(
+                MEMORY_INFO_LIST_STREAM,
+                memory_info_list(64, 1, &[(0x10_0000, 0x1000)]),
+            )
+            .build();
+        let overview = block_on(parse_overview(&extended, &options)).unwrap();
+        let memory = overview.memory_data.unwrap();
+        assert!(memory.has_memory_info_stream);
+        let map = memory.memory_info.unwrap();
+        assert_eq!(map.ranges_count, 1);
+        assert_eq!(map.list_header.unwrap().size_of_entry, 64);
+
+        // Standard entries go through the minidump crate and still report the header
+        let standard = DumpBuilder::new()
+            .memory(0x10_0000, vec![0; 0x1000])
+            .memory_info(MemoryInfo {
+                base: 0x10_0000,
+                allocation_base: 0x10_0000,
+                allocation_protect: PAGE_READWRITE,
+                size: 0x1000,
+                state: MEM_COMMIT,
+                protect: PAGE_READWRITE,
+                kind: MEM_PRIVATE,
+            })
+            .build();
+        let overview = block_on(parse_overview(&standard, &options)).unwrap();
+        let map = overview.memory_data.unwrap().memory_info.unwrap();
+        assert_eq!(map.ranges_count, 1);
+        assert!(map.list_header.unwrap().entry_size_matches);
+    }
+}
@@ -0,0 +1,33 @@
+use minidump::MinidumpMiscInfo;
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct MiscInfoData {
+    pub process_id: Option<u32>,
+    // Mandatory integrity level RID of the process token (MiscInfo 3+), e.g. 0x1000 for a
+    // sandboxed (Low) process
+    pub process_integrity_level: Option<u32>,
+    pub process_integrity_level_name: Option<&'static str>,
+}
+
+pub fn parse_misc_info(misc_info: &MinidumpMiscInfo) -> MiscInfoData {
+    // The accessors return None unless the struct version has the field and its flag is set
+    let process_integrity_level = misc_info.raw.process_integrity_level().copied();
+    MiscInfoData {
+        process_id: misc_info.raw.process_id().copied(),
+        process_integrity_level,
+        process_integrity_level_name: process_integrity_level.map(integrity_level_name),
+    }
+}
+
+// SECURITY_MANDATORY_*_RID bands (winnt.h); values between two levels, such as
+// Medium Plus (0x2100), belong to the lower one
+fn integrity_level_name(level: u32) -> &'static str {
+    match level {
+        0x0000..0x1000 => "Untrusted",
+        0x1000..0x2000 => "Low",
+        0x2000..0x3000 => "Medium",
+        0x3000..0x4000 => "High",
+        _ => "System",
+    }
+}
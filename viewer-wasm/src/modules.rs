@@ -1,24 +1,48 @@
-use crate::common::{SafeU64, debug_output};
-use minidump::MinidumpModuleList;
+use crate::common::{SafeU64, debug_output, read_u32_le};
+use crate::memory::{MEM_COMMIT, is_executable, memory_at};
+use crate::options::ParseOptions;
+use crate::time::format_utc;
+use minidump::system_info::Cpu;
+use minidump::{
+    MinidumpMemoryInfoList, MinidumpModule, MinidumpModuleList, MinidumpUnloadedModuleList, Module,
+    UnifiedMemoryList,
+};
 use serde::Serialize;
+use std::sync::OnceLock;
 
 #[derive(Serialize)]
 pub struct ModuleData {
     pub modules: Vec<ModuleInfo>,
     pub modules_count: usize,
+    pub module_stats: ModuleStats,
     pub debug: Option<String>,
 }
 
+#[derive(Serialize, Default)]
+pub struct ModuleStats {
+    pub with_version: usize,
+    pub with_codeview: usize, // Modules with a CodeView record, i.e. a debug id to symbolicate with
+    pub system: usize,        // Classified with the same heuristic as `ModuleInfo.is_system`
+    pub app: usize,
+}
+
 #[derive(Serialize)]
 pub struct ModuleInfo {
-    pub name: String,
+    pub name: String, // Placeholder "<unknown module @ 0x...>" when the dump's name is empty
+    pub name_valid: bool, // False if the name was empty or had undecodable characters
+    pub normalized_name: String, // See normalized_module_name; `name` is kept for display
     pub base_of_image: String, // Formatted as hex
     pub size_of_image: u32,
     pub checksum: u32,
     pub time_date_stamp: u32,
     pub version_info: Option<VersionInfo>,
     pub cv_record_info: Option<CodeViewInfo>,
-    pub misc_record_present: bool,
+    pub misc_record: Option<MiscRecordInfo>, // Decoded IMAGE_DEBUG_MISC record, None if absent
+    pub is_system: Option<bool>, // Only set when system module classification is requested
+    // Which rule classified the module: "path", "known_name" or "wine"
+    pub system_heuristic: Option<String>,
+    // CRC32 of the module's captured executable memory, see module_code_crc32
+    pub code_crc32: Option<u32>,
 }
 
 #[derive(Serialize)]
@@ -30,33 +54,79 @@ pub struct VersionInfo {
     pub file_os: Option<String>,
 }
 
+#[derive(Serialize)]
+pub struct MiscRecordInfo {
+    pub flags: u32,    // IMAGE_DEBUG_MISC DataType, 1 = IMAGE_DEBUG_MISC_EXENAME
+    pub unicode: bool, // Whether the data is UTF-16 rather than ANSI
+    pub debug_path: Option<String>,
+}
+
 #[derive(Serialize)]
 pub struct CodeViewInfo {
     pub format: String,
     pub identifier: Option<String>,
     pub age: Option<u32>,
     pub pdb_filename: Option<String>,
+    // Symbol-server index of the PDB: signature then age, uppercase hex with no separators
+    pub debug_identifier: Option<String>,
 }
 
-pub fn parse_modules_data(modules: &MinidumpModuleList) -> ModuleData {
+// Modules are kept in the order of the module list stream, which is stable for a given dump
+// and conventionally starts with the main executable.
+pub fn parse_modules_data(
+    modules: &MinidumpModuleList,
+    dump_bytes: &[u8],
+    memory: Option<&UnifiedMemoryList>,
+    memory_info: Option<&MinidumpMemoryInfoList>,
+    options: &ParseOptions,
+) -> ModuleData {
+    let mut module_stats = ModuleStats::default();
+    let wine = is_wine_dump(modules);
     let parsed_modules = modules
         .iter()
         .map(|module| {
-            let name = module.name.clone();
             let base_of_image = SafeU64::from(module.raw.base_of_image)
                 .to_hex_string()
                 .to_string();
+            let (name, name_valid) = sanitize_module_name(&module.name, &base_of_image);
             let raw = &module.raw;
 
+            let heuristic = classify_system_module(&name, wine);
+            let (is_system, system_heuristic) = if options.classify_system_modules {
+                (Some(heuristic.is_some()), heuristic.map(|h| h.to_string()))
+            } else {
+                (None, None)
+            };
+
+            let version_info = parse_version_info(&raw.version_info);
+            let cv_record_info = module.codeview_info.as_ref().and_then(parse_codeview_info);
+            module_stats.with_version += version_info.is_some() as usize;
+            module_stats.with_codeview += cv_record_info.is_some() as usize;
+            if heuristic.is_some() {
+                module_stats.system += 1;
+            } else {
+                module_stats.app += 1;
+            }
+
             ModuleInfo {
+                normalized_name: normalized_module_name(&name),
                 name,
+                name_valid,
                 base_of_image,
                 size_of_image: raw.size_of_image,
                 checksum: raw.checksum,
                 time_date_stamp: raw.time_date_stamp,
-                version_info: parse_version_info(&raw.version_info),
-                cv_record_info: module.codeview_info.as_ref().and_then(parse_codeview_info),
-                misc_record_present: raw.misc_record.data_size > 0,
+                version_info,
+                cv_record_info,
+                misc_record: parse_misc_record(dump_bytes, &raw.misc_record),
+                is_system,
+                system_heuristic,
+                code_crc32: match (memory, memory_info) {
+                    (Some(memory), Some(memory_info)) if options.module_code_crc32 => {
+                        module_code_crc32(module, memory, memory_info)
+                    }
+                    _ => None,
+                },
             }
         })
         .collect();
@@ -64,10 +134,130 @@ pub fn parse_modules_data(modules: &MinidumpModuleList) -> ModuleData {
     ModuleData {
         modules: parsed_modules,
         modules_count: modules.iter().count(),
+        module_stats,
         debug: debug_output(modules),
     }
 }
 
+// CRC32 over the bytes of the module's committed executable pages that the dump captured,
+// concatenated in address order, to compare against the same pages of a known-good binary.
+// It only covers what was captured: pages missing from the dump are skipped rather than
+// treated as zeros, so a partial capture gives a different checksum than the full code.
+// Which pages are executable comes from the MemoryInfo stream; None without any captured
+// executable bytes.
+fn module_code_crc32(
+    module: &MinidumpModule,
+    memory: &UnifiedMemoryList,
+    memory_info: &MinidumpMemoryInfoList,
+) -> Option<u32> {
+    let start = module.raw.base_of_image;
+    let end = start.saturating_add(module.raw.size_of_image as u64);
+
+    let mut code_ranges: Vec<(u64, u64)> = memory_info
+        .iter()
+        .filter(|info| info.raw.state == MEM_COMMIT && is_executable(info.raw.protection))
+        .map(|info| {
+            let base = info.raw.base_address;
+            (
+                base.max(start),
+                base.saturating_add(info.raw.region_size).min(end),
+            )
+        })
+        .filter(|(base, range_end)| base < range_end)
+        .collect();
+    code_ranges.sort_unstable();
+
+    let mut captured: Vec<(u64, &[u8])> = memory
+        .iter()
+        .filter(|region| {
+            region.base_address() < end
+                && region.base_address().saturating_add(region.size()) > start
+        })
+        .map(|region| (region.base_address(), region.bytes()))
+        .collect();
+    captured.sort_unstable_by_key(|&(base, _)| base);
+
+    let mut crc = flate2::Crc::new();
+    for &(range_start, range_end) in &code_ranges {
+        for &(base, bytes) in &captured {
+            let low = range_start.max(base);
+            let high = range_end.min(base.saturating_add(bytes.len() as u64));
+            if low < high {
+                crc.update(&bytes[(low - base) as usize..(high - base) as usize]);
+            }
+        }
+    }
+    (crc.amount() > 0).then(|| crc.sum())
+}
+
+// The key modules are matched on everywhere (version manifests, module lookups by name, crash
+// signatures): the file name without its directory, lowercased, so "C:\\Windows\\FOO.DLL"
+// and "foo.dll" are the same module
+pub fn normalized_module_name(path: &str) -> String {
+    module_file_name(path).to_lowercase()
+}
+
+// The file name of a module path, for display; see normalized_module_name for matching
+pub fn module_file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+// Empty names get a placeholder so the module stays addressable in the UI; names containing
+// U+FFFD (a bad UTF-16 decode upstream) are kept as-is but flagged
+fn sanitize_module_name(name: &str, base_of_image: &str) -> (String, bool) {
+    if name.trim().is_empty() {
+        (format!("<unknown module @ {}>", base_of_image), false)
+    } else {
+        (
+            name.to_string(),
+            !name.contains(char::REPLACEMENT_CHARACTER),
+        )
+    }
+}
+
+// Size of the IMAGE_DEBUG_MISC header preceding the variable-length data
+const IMAGE_DEBUG_MISC_HEADER_SIZE: usize = 12;
+
+// The minidump crate doesn't decode misc records, so read the IMAGE_DEBUG_MISC directly from
+// the dump. The data is a NUL-terminated path in either ANSI or UTF-16LE.
+fn parse_misc_record(
+    dump_bytes: &[u8],
+    location: &minidump::format::MINIDUMP_LOCATION_DESCRIPTOR,
+) -> Option<MiscRecordInfo> {
+    if location.data_size == 0 {
+        return None;
+    }
+    let start = location.rva as usize;
+    let end = start.checked_add(location.data_size as usize)?;
+    let record = dump_bytes.get(start..end)?;
+
+    let flags = read_u32_le(record, 0)?;
+    let length = read_u32_le(record, 4)? as usize;
+    let unicode = *record.get(8)? != 0;
+
+    // `length` covers the whole record including its header; trust it only within the stream
+    let data_end = length.clamp(IMAGE_DEBUG_MISC_HEADER_SIZE, record.len());
+    let data = &record[IMAGE_DEBUG_MISC_HEADER_SIZE.min(data_end)..data_end];
+
+    let debug_path = if unicode {
+        let units: Vec<u16> = data
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .take_while(|&u| u != 0)
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        let text = data.split(|&b| b == 0).next().unwrap_or_default();
+        String::from_utf8_lossy(text).into_owned()
+    };
+
+    Some(MiscRecordInfo {
+        flags,
+        unicode,
+        debug_path: (!debug_path.is_empty()).then_some(debug_path),
+    })
+}
+
 // Helper function to format version from high and low parts
 fn format_version(version_hi: u32, version_lo: u32) -> Option<String> {
     if version_hi != 0 || version_lo != 0 {
@@ -82,7 +272,9 @@ fn format_version(version_hi: u32, version_lo: u32) -> Option<String> {
 }
 
 // For now, create a simple version info parser
-fn parse_version_info(version_info: &minidump::format::VS_FIXEDFILEINFO) -> Option<VersionInfo> {
+pub fn parse_version_info(
+    version_info: &minidump::format::VS_FIXEDFILEINFO,
+) -> Option<VersionInfo> {
     // Check if version info is valid (signature should be 0xFEEF04BD)
     if version_info.signature != 0xFEEF04BD {
         return None;
@@ -165,36 +357,50 @@ fn parse_file_os(file_os: u32) -> Option<String> {
     }
 }
 
+// The PDB name of a CodeView record, without the NUL terminator the record stores it with
+fn pdb_file_name(bytes: &[u8]) -> Option<String> {
+    let name = bytes.split(|&b| b == 0).next().unwrap_or_default();
+    if name.is_empty() {
+        None
+    } else {
+        String::from_utf8(name.to_vec()).ok()
+    }
+}
+
 fn parse_codeview_info(cv: &minidump::CodeView) -> Option<CodeViewInfo> {
     match cv {
-        minidump::CodeView::Pdb70(pdb70) => {
-            // Convert Vec<u8> to String for PDB filename
-            let pdb_filename = if pdb70.pdb_file_name.is_empty() {
-                None
-            } else {
-                String::from_utf8(pdb70.pdb_file_name.clone()).ok()
-            };
-
-            Some(CodeViewInfo {
-                format: "PDB70".to_string(),
-                identifier: Some(format!("{}", pdb70.signature)),
-                age: Some(pdb70.age),
-                pdb_filename,
-            })
-        }
+        minidump::CodeView::Pdb70(pdb70) => Some(CodeViewInfo {
+            format: "PDB70".to_string(),
+            identifier: Some(format!("{}", pdb70.signature)),
+            age: Some(pdb70.age),
+            pdb_filename: pdb_file_name(&pdb70.pdb_file_name),
+            debug_identifier: Some(format!(
+                "{:08X}{:04X}{:04X}{}{:X}",
+                pdb70.signature.data1,
+                pdb70.signature.data2,
+                pdb70.signature.data3,
+                pdb70
+                    .signature
+                    .data4
+                    .iter()
+                    .map(|b| format!("{:02X}", b))
+                    .collect::<String>(),
+                pdb70.age
+            )),
+        }),
         minidump::CodeView::Pdb20(pdb20) => {
-            // Convert Vec<u8> to String for PDB filename
-            let pdb_filename = if pdb20.pdb_file_name.is_empty() {
-                None
-            } else {
-                String::from_utf8(pdb20.pdb_file_name.clone()).ok()
-            };
-
+            // An NB10 signature is the PDB's creation time (seconds since the Unix epoch)
+            // rather than a GUID, so show it as a date
             Some(CodeViewInfo {
                 format: "PDB20".to_string(),
-                identifier: Some(format!("{:08x}{:08x}", pdb20.signature, pdb20.age)),
+                identifier: Some(format!(
+                    "{} age {}",
+                    format_utc(pdb20.signature as i64),
+                    pdb20.age
+                )),
                 age: Some(pdb20.age),
-                pdb_filename,
+                pdb_filename: pdb_file_name(&pdb20.pdb_file_name),
+                debug_identifier: Some(format!("{:08X}{:X}", pdb20.signature, pdb20.age)),
             })
         }
         minidump::CodeView::Elf(elf) => {
@@ -210,6 +416,7 @@ fn parse_codeview_info(cv: &minidump::CodeView) -> Option<CodeViewInfo> {
                 identifier: Some(build_id_hex),
                 age: None,
                 pdb_filename: None,
+                debug_identifier: None,
             })
         }
         _ => Some(CodeViewInfo {
@@ -217,10 +424,578 @@ fn parse_codeview_info(cv: &minidump::CodeView) -> Option<CodeViewInfo> {
             identifier: None,
             age: None,
             pdb_filename: None,
+            debug_identifier: None,
         }),
     }
 }
 
+// Conservative system module classification: a module is only tagged as a system module
+// when it lives under a well-known OS directory or has a well-known OS library name. In a
+// Wine dump, Wine's own Unix-side libraries count as system modules too, wherever the
+// install lives (Proton ships them inside the game library). There is no version info
+// heuristic: a dump only records a module's VS_FIXEDFILEINFO, which has version numbers but
+// not the CompanyName string ("Microsoft Corporation") from its resources.
+// Returns the heuristic that matched, or None for application modules.
+fn classify_system_module(path: &str, wine: bool) -> Option<&'static str> {
+    let lower_path = path.to_lowercase().replace('\\', "/");
+
+    if wine && is_wine_module(path) {
+        return Some("wine");
+    }
+
+    // Compare directories from the root, ignoring any `\\?\` prefix and drive letter
+    let rooted_path = lower_path.trim_start_matches("//?/");
+    let rooted_path = match rooted_path.as_bytes() {
+        [_, b':', ..] => &rooted_path[2..],
+        _ => rooted_path,
+    };
+
+    if get_system_directories()
+        .iter()
+        .any(|dir| rooted_path.starts_with(dir))
+    {
+        return Some("path");
+    }
+
+    let basename = lower_path.rsplit('/').next().unwrap_or(&lower_path);
+    if get_system_module_names()
+        .iter()
+        .any(|known| basename == *known || basename.starts_with(&format!("{}.", known)))
+    {
+        return Some("known_name");
+    }
+
+    None
+}
+
+// Library directories of a Wine install, e.g. /usr/lib/wine/x86_64-unix/ or Proton's
+// files/lib64/wine/
+const WINE_DIRECTORIES: &[&str] = &["/lib/wine/", "/lib32/wine/", "/lib64/wine/"];
+
+// Wine's loader and the Unix halves of its core DLLs
+const WINE_MODULE_NAMES: &[&str] = &[
+    "wine",
+    "wine64",
+    "wine-preloader",
+    "wine64-preloader",
+    "ntdll.so",
+    "win32u.so",
+];
+
+// Wine and Proton dumps list Windows modules next to Linux shared objects. Any module from a
+// Wine library directory, one of Wine's Unix-side modules, or an old-style builtin DLL
+// ("kernel32.dll.so") means the process ran under Wine. Version strings aren't checked: the
+// SystemInfo and MiscInfo streams carry the Windows version Wine emulates, and Wine's own
+// version is only returned by ntdll's `wine_get_version` export, whose code isn't in the dump.
+pub fn is_wine_dump(modules: &MinidumpModuleList) -> bool {
+    modules.iter().any(|module| is_wine_module(&module.name))
+}
+
+fn is_wine_module(path: &str) -> bool {
+    let lower_path = path.to_lowercase().replace('\\', "/");
+    let basename = normalized_module_name(path);
+    WINE_DIRECTORIES.iter().any(|dir| lower_path.contains(dir))
+        || WINE_MODULE_NAMES.contains(&basename.as_str())
+        || basename.ends_with(".dll.so")
+        || basename.ends_with(".drv.so")
+}
+
+static SYSTEM_DIRECTORIES: OnceLock<Vec<&'static str>> = OnceLock::new();
+static SYSTEM_MODULE_NAMES: OnceLock<Vec<&'static str>> = OnceLock::new();
+
+fn get_system_directories() -> &'static Vec<&'static str> {
+    SYSTEM_DIRECTORIES.get_or_init(|| {
+        vec![
+            // Windows
+            "/windows/system32/",
+            "/windows/syswow64/",
+            "/windows/winsxs/",
+            // Linux
+            "/usr/lib/",
+            "/usr/lib64/",
+            "/lib/",
+            "/lib64/",
+            // Android
+            "/system/lib/",
+            "/system/lib64/",
+            "/apex/",
+            // macOS / iOS
+            "/system/library/",
+            "/usr/lib/system/",
+        ]
+    })
+}
+
+fn get_system_module_names() -> &'static Vec<&'static str> {
+    SYSTEM_MODULE_NAMES.get_or_init(|| {
+        vec![
+            // Windows
+            "ntdll.dll",
+            "kernel32.dll",
+            "kernelbase.dll",
+            "user32.dll",
+            "gdi32.dll",
+            "advapi32.dll",
+            "ole32.dll",
+            "combase.dll",
+            "rpcrt4.dll",
+            "ucrtbase.dll",
+            "msvcrt.dll",
+            "win32u.dll",
+            "sechost.dll",
+            // Linux
+            "libc.so",
+            "libm.so",
+            "libpthread.so",
+            "libdl.so",
+            "ld-linux-x86-64.so",
+            "ld-linux-aarch64.so",
+            "linux-vdso.so",
+            "linux-gate.so",
+            // macOS
+            "dyld",
+            "libsystem_kernel.dylib",
+            "libsystem_c.dylib",
+            "libsystem_pthread.dylib",
+        ]
+    })
+}
+
+// Names of modules whose image header (read from captured memory) declares a different
+// architecture than the system info CPU, e.g. x64 modules emulated on an ARM64 process.
+// Modules whose header wasn't captured are skipped.
+pub fn find_mixed_architecture_modules(
+    modules: &MinidumpModuleList,
+    memory: &UnifiedMemoryList,
+    cpu: Cpu,
+) -> Vec<String> {
+    let Some(system_arch) = cpu_architecture_name(cpu) else {
+        return Vec::new();
+    };
+
+    modules
+        .iter()
+        .filter(|module| {
+            module_architecture(module, memory).is_some_and(|arch| arch != system_arch)
+        })
+        .map(|module| module.name.clone())
+        .collect()
+}
+
+fn cpu_architecture_name(cpu: Cpu) -> Option<&'static str> {
+    match cpu {
+        Cpu::X86 => Some("x86"),
+        Cpu::X86_64 => Some("amd64"),
+        Cpu::Arm => Some("arm"),
+        Cpu::Arm64 => Some("arm64"),
+        _ => None,
+    }
+}
+
+// Size of the image header we read to find the machine type; covers the PE optional header
+// offset (e_lfanew) for typical binaries as well as ELF and Mach-O headers
+const IMAGE_HEADER_READ_SIZE: usize = 0x400;
+
+// IMAGE_SECTION_HEADER.Characteristics bit for sections holding code
+const IMAGE_SCN_MEM_EXECUTE: u32 = 0x2000_0000;
+
+// Address ranges of a PE module's executable sections, read from its section table in
+// captured memory. None when the header isn't captured or isn't a PE header.
+pub(crate) fn pe_executable_sections(
+    module: &MinidumpModule,
+    memory: &UnifiedMemoryList,
+) -> Option<Vec<(u64, u64)>> {
+    let base = module.raw.base_of_image;
+    let header = memory_at(memory, base, IMAGE_HEADER_READ_SIZE)?;
+    if !header.starts_with(b"MZ") {
+        return None;
+    }
+    let pe_offset = read_u32_le(header, 0x3c)? as usize;
+    if header.get(pe_offset..pe_offset.checked_add(4)?)? != b"PE\0\0" {
+        return None;
+    }
+    // COFF header: NumberOfSections at +6, SizeOfOptionalHeader at +20; the 40-byte section
+    // headers follow the optional header
+    let field = |offset: usize| {
+        let bytes = header.get(pe_offset + offset..pe_offset + offset + 2)?;
+        Some(u16::from_le_bytes([bytes[0], bytes[1]]) as usize)
+    };
+    let section_count = field(6)?;
+    let sections = pe_offset + 24 + field(20)?;
+    let sections = (0..section_count)
+        .map_while(|index| {
+            let section = sections + index * 40;
+            let virtual_size = read_u32_le(header, section + 8)?;
+            let virtual_address = read_u32_le(header, section + 12)?;
+            let characteristics = read_u32_le(header, section + 36)?;
+            Some((virtual_address, virtual_size, characteristics))
+        })
+        .filter(|&(_, _, characteristics)| characteristics & IMAGE_SCN_MEM_EXECUTE != 0)
+        .map(|(virtual_address, virtual_size, _)| {
+            let start = base.saturating_add(virtual_address as u64);
+            (start, start.saturating_add(virtual_size as u64))
+        })
+        .collect();
+    Some(sections)
+}
+
+// Determine a module's architecture from its PE, ELF or Mach-O header in captured memory
+fn module_architecture(
+    module: &MinidumpModule,
+    memory: &UnifiedMemoryList,
+) -> Option<&'static str> {
+    let header = memory_at(memory, module.raw.base_of_image, IMAGE_HEADER_READ_SIZE)?;
+
+    if header.starts_with(b"MZ") {
+        // PE: e_lfanew at 0x3c points to "PE\0\0" followed by the u16 Machine field
+        let pe_offset = read_u32_le(header, 0x3c)? as usize;
+        if header.get(pe_offset..pe_offset.checked_add(4)?)? != b"PE\0\0" {
+            return None;
+        }
+        let machine = header.get(pe_offset + 4..pe_offset + 6)?;
+        return match u16::from_le_bytes([machine[0], machine[1]]) {
+            0x014c => Some("x86"),
+            0x8664 => Some("amd64"),
+            0x01c0 | 0x01c4 => Some("arm"),
+            0xaa64 => Some("arm64"),
+            _ => None,
+        };
+    }
+
+    if header.starts_with(b"\x7fELF") {
+        // ELF: e_machine is a u16 at offset 18 (assuming a little-endian image)
+        let machine = header.get(18..20)?;
+        return match u16::from_le_bytes([machine[0], machine[1]]) {
+            3 => Some("x86"),
+            62 => Some("amd64"),
+            40 => Some("arm"),
+            183 => Some("arm64"),
+            _ => None,
+        };
+    }
+
+    match read_u32_le(header, 0)? {
+        // Mach-O: cputype follows the magic
+        0xfeedface | 0xfeedfacf => match read_u32_le(header, 4)? {
+            0x0000_0007 => Some("x86"),
+            0x0100_0007 => Some("amd64"),
+            0x0000_000c => Some("arm"),
+            0x0100_000c => Some("arm64"),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 pub fn get_modules_count(modules: &MinidumpModuleList) -> usize {
     modules.iter().count()
 }
+
+// Address -> module lookup, built once per parse so resolving every frame doesn't rescan the
+// module list. `ranges` holds (base, end, index into `modules`) sorted by base.
+pub struct ModuleIndex<'a> {
+    modules: Vec<&'a MinidumpModule>,
+    ranges: Vec<(u64, u64, usize)>,
+}
+
+impl<'a> ModuleIndex<'a> {
+    pub fn new(modules: &'a MinidumpModuleList) -> Self {
+        let modules: Vec<&MinidumpModule> = modules.iter().collect();
+        let mut ranges: Vec<(u64, u64, usize)> = modules
+            .iter()
+            .enumerate()
+            .map(|(index, module)| {
+                let base = module.raw.base_of_image;
+                let end = base.saturating_add(module.raw.size_of_image as u64);
+                (base, end, index)
+            })
+            .collect();
+        ranges.sort_unstable();
+        ModuleIndex { modules, ranges }
+    }
+
+    // The module whose [base, end) range contains `address`
+    pub fn module_for_address(&self, address: u64) -> Option<&'a MinidumpModule> {
+        // First range starting past `address`; the candidate is the one before it
+        let next = self.ranges.partition_point(|&(base, _, _)| base <= address);
+        let &(_, end, index) = self.ranges.get(next.checked_sub(1)?)?;
+        (address < end).then(|| self.modules[index])
+    }
+
+    // The first module with the same normalized name as `name`, which may be a full path or
+    // just a file name
+    pub fn module_named(&self, name: &str) -> Option<&'a MinidumpModule> {
+        let name = normalized_module_name(name);
+        self.modules
+            .iter()
+            .copied()
+            .find(|module| normalized_module_name(&module.name) == name)
+    }
+}
+
+// Identifiers a symbol server is queried with, per module. The ids come from the module's
+// CodeView record (debug id) and PE header / ELF build id (code id).
+#[derive(Serialize, PartialEq)]
+pub struct SymbolModule {
+    pub code_file: String,
+    pub code_id: Option<String>,
+    pub debug_file: Option<String>,
+    pub debug_id: Option<String>, // Breakpad form: GUID/build id followed by the age
+}
+
+pub fn list_modules_for_symbols(modules: &MinidumpModuleList) -> Vec<SymbolModule> {
+    modules.iter().map(|module| symbol_module(module)).collect()
+}
+
+// Every distinct module a symbol server has to be asked about, loaded modules first, then
+// unloaded ones. Modules without a debug id can't be looked up and are left out.
+pub fn collect_required_symbols(
+    modules: Option<&MinidumpModuleList>,
+    unloaded_modules: Option<&MinidumpUnloadedModuleList>,
+) -> Vec<SymbolModule> {
+    let loaded = modules
+        .into_iter()
+        .flat_map(|modules| modules.iter().map(|module| symbol_module(module)));
+    let unloaded = unloaded_modules
+        .into_iter()
+        .flat_map(|modules| modules.iter().map(|module| symbol_module(module)));
+
+    let mut required: Vec<SymbolModule> = Vec::new();
+    for module in loaded.chain(unloaded) {
+        if module.debug_id.is_some() && !required.contains(&module) {
+            required.push(module);
+        }
+    }
+    required
+}
+
+fn symbol_module(module: &dyn Module) -> SymbolModule {
+    SymbolModule {
+        code_file: module.code_file().into_owned(),
+        code_id: module.code_identifier().map(|id| id.to_string()),
+        debug_file: module.debug_file().map(|file| file.into_owned()),
+        debug_id: module
+            .debug_identifier()
+            .map(|id| id.breakpad().to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_overview;
+    use crate::testing::{
+        DumpBuilder, Module as TestModule, PLATFORM_WIN32_NT, PROCESSOR_ARCHITECTURE_AMD64,
+        SystemInfo, block_on,
+    };
+    use minidump::Minidump;
+
+    fn modules_data(modules: Vec<TestModule>, options: &ParseOptions) -> ModuleData {
+        let bytes = modules
+            .into_iter()
+            .fold(DumpBuilder::new(), DumpBuilder::module)
+            .build();
+        let dump = Minidump::read(bytes.as_slice()).unwrap();
+        let modules = dump.get_stream::<MinidumpModuleList>().unwrap();
+        parse_modules_data(&modules, &bytes, None, None, options)
+    }
+
+    #[test]
+    fn classifies_system_modules_conservatively() {
+        let cases = [
+            ("C:\\Windows\\System32\\ntdll.dll", Some("path")),
+            ("\\\\?\\C:\\WINDOWS\\SysWOW64\\user32.dll", Some("path")),
+            ("/usr/lib/x86_64-linux-gnu/libssl.so.3", Some("path")),
+            ("/system/lib64/libbinder.so", Some("path")),
+            ("D:\\tools\\KERNEL32.DLL", Some("known_name")),
+            ("/opt/app/libc.so.6", Some("known_name")),
+            ("C:\\app\\app.exe", None),
+            ("/opt/app/libcurl.so.4", None),
+            ("/home/me/lib/libfoo.so", None),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(classify_system_module(path, false), expected, "{}", path);
+        }
+    }
+
+    #[test]
+    fn tags_system_modules_only_when_asked() {
+        let modules = || {
+            vec![
+                TestModule::new(0x1_4000_0000, 0x10000, "C:\\app\\app.exe"),
+                TestModule::new(
+                    0x7ff8_0000_0000,
+                    0x10000,
+                    "C:\\Windows\\System32\\ntdll.dll",
+                ),
+            ]
+        };
+
+        let data = modules_data(modules(), &ParseOptions::default());
+        assert!(data.modules.iter().all(|m| m.is_system.is_none()));
+        assert!(data.modules.iter().all(|m| m.system_heuristic.is_none()));
+
+        let options = ParseOptions {
+            classify_system_modules: true,
+            ..ParseOptions::default()
+        };
+        let data = modules_data(modules(), &options);
+        assert_eq!(data.modules[0].is_system, Some(false));
+        assert_eq!(data.modules[0].system_heuristic, None);
+        assert_eq!(data.modules[1].is_system, Some(true));
+        assert_eq!(data.modules[1].system_heuristic.as_deref(), Some("path"));
+    }
+
+    #[test]
+    fn labels_wine_dumps_and_their_system_modules() {
+        let proton = "/home/me/.steam/steam/steamapps/common/Proton 9.0/files";
+        let names = [
+            format!("{}/lib64/wine/x86_64-unix/ntdll.so", proton),
+            "/opt/wine-staging/kernel32.dll.so".to_string(),
+            format!("{}/lib64/wine/x86_64-unix/winevulkan.so", proton),
+            "C:\\games\\game.exe".to_string(),
+        ];
+        let bytes = names
+            .iter()
+            .enumerate()
+            .fold(
+                DumpBuilder::new().system_info(SystemInfo::new(
+                    PROCESSOR_ARCHITECTURE_AMD64,
+                    PLATFORM_WIN32_NT,
+                )),
+                |builder, (index, name)| {
+                    let base = 0x7f00_0000_0000 + index as u64 * 0x10_0000;
+                    builder.module(TestModule::new(base, 0x10000, name))
+                },
+            )
+            .build();
+        let dump = Minidump::read(bytes.as_slice()).unwrap();
+        assert!(is_wine_dump(
+            &dump.get_stream::<MinidumpModuleList>().unwrap()
+        ));
+
+        let options = ParseOptions {
+            classify_system_modules: true,
+            ..ParseOptions::default()
+        };
+        let overview = block_on(parse_overview(&bytes, &options)).unwrap();
+        let system = overview.system_info.unwrap();
+        assert!(system.wine);
+        assert!(system.os_display.ends_with(" (Wine)"));
+        let heuristics: Vec<Option<&str>> = overview
+            .modules_data
+            .as_ref()
+            .unwrap()
+            .modules
+            .iter()
+            .map(|m| m.system_heuristic.as_deref())
+            .collect();
+        assert_eq!(heuristics, [Some("wine"), Some("wine"), Some("wine"), None]);
+
+        // Outside a Wine dump the same names get no Wine treatment
+        assert_eq!(classify_system_module(&names[2], false), None);
+        let bytes = DumpBuilder::new()
+            .module(TestModule::new(
+                0x1_4000_0000,
+                0x10000,
+                "C:\\games\\game.exe",
+            ))
+            .build();
+        let dump = Minidump::read(bytes.as_slice()).unwrap();
+        assert!(!is_wine_dump(
+            &dump.get_stream::<MinidumpModuleList>().unwrap()
+        ));
+    }
+
+    #[test]
+    fn names_unnamed_modules_and_flags_bad_decodes() {
+        let data = modules_data(
+            vec![
+                TestModule::new(0x1_4000_0000, 0x10000, ""),
+                TestModule::new(0x1_5000_0000, 0x10000, "C:\\app\\app.exe"),
+            ],
+            &ParseOptions::default(),
+        );
+        assert_eq!(data.modules[0].name, "<unknown module @ 0x140000000>");
+        assert!(!data.modules[0].name_valid);
+        assert!(data.modules[1].name_valid);
+
+        assert_eq!(
+            sanitize_module_name("  ", "0x1000"),
+            ("<unknown module @ 0x1000>".to_string(), false)
+        );
+        let bad = String::from_utf16_lossy(&[0x0061, 0xd800, 0x0062]);
+        assert_eq!(sanitize_module_name(&bad, "0x1000"), (bad.clone(), false));
+    }
+
+    #[test]
+    fn module_index_finds_modules_by_address_and_name() {
+        // Listed out of address order, with a gap between the two DLLs
+        let bytes = DumpBuilder::new()
+            .module(TestModule::new(
+                0x7ff8_0000_0000,
+                0x2000,
+                "C:\\Windows\\ntdll.dll",
+            ))
+            .module(TestModule::new(0x1_4000_0000, 0x10000, "C:\\app\\app.exe"))
+            .module(TestModule::new(
+                0x7ff7_0000_0000,
+                0x1000,
+                "C:\\app\\helper.dll",
+            ))
+            .build();
+        let dump = Minidump::read(bytes.as_slice()).unwrap();
+        let modules = dump.get_stream::<MinidumpModuleList>().unwrap();
+        let index = ModuleIndex::new(&modules);
+
+        let name_at = |address| index.module_for_address(address).map(|m| m.name.as_str());
+        assert_eq!(name_at(0x1_4000_0000), Some("C:\\app\\app.exe"));
+        assert_eq!(name_at(0x1_4000_ffff), Some("C:\\app\\app.exe"));
+        assert_eq!(name_at(0x1_4001_0000), None); // End is exclusive
+        assert_eq!(name_at(0x7ff7_0000_0800), Some("C:\\app\\helper.dll"));
+        assert_eq!(name_at(0x7ff7_8000_0000), None);
+        assert_eq!(name_at(0x7ff8_0000_1000), Some("C:\\Windows\\ntdll.dll"));
+        assert_eq!(name_at(0x1000), None);
+        assert_eq!(name_at(u64::MAX), None);
+
+        let base_of = |name| index.module_named(name).map(|m| m.raw.base_of_image);
+        assert_eq!(base_of("NTDLL.DLL"), Some(0x7ff8_0000_0000));
+        assert_eq!(base_of("D:\\other\\helper.dll"), Some(0x7ff7_0000_0000));
+        assert_eq!(base_of("kernel32.dll"), None);
+    }
+
+    #[test]
+    fn formats_codeview_identifiers() {
+        // CV_INFO_PDB20: "NB10", offset, signature (a timestamp), age, PDB name
+        let mut nb10 = TestModule::new(0x1_5000_0000, 0x10000, "C:\\app\\old.dll");
+        nb10.cv_record = b"NB10".to_vec();
+        nb10.cv_record.extend_from_slice(&0u32.to_le_bytes());
+        nb10.cv_record
+            .extend_from_slice(&1714566896u32.to_le_bytes());
+        nb10.cv_record.extend_from_slice(&3u32.to_le_bytes());
+        nb10.cv_record.extend_from_slice(b"old.pdb\0");
+
+        let guid = [
+            0x78, 0x56, 0x34, 0x12, 0xbc, 0x9a, 0xf0, 0xde, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab,
+            0xcd, 0xef,
+        ];
+        let pdb70 = TestModule::new(0x1_4000_0000, 0x10000, "C:\\app\\app.exe")
+            .with_pdb70(guid, 0x2a, "app.pdb");
+
+        let data = modules_data(vec![pdb70, nb10], &ParseOptions::default());
+        let cv = data.modules[0].cv_record_info.as_ref().unwrap();
+        assert_eq!(cv.format, "PDB70");
+        assert_eq!(cv.pdb_filename.as_deref(), Some("app.pdb"));
+        assert_eq!(
+            cv.debug_identifier.as_deref(),
+            Some("123456789ABCDEF00123456789ABCDEF2A")
+        );
+
+        let cv = data.modules[1].cv_record_info.as_ref().unwrap();
+        assert_eq!(cv.format, "PDB20");
+        assert_eq!(cv.identifier.as_deref(), Some("2024-05-01T12:34:56Z age 3"));
+        assert_eq!(cv.age, Some(3));
+        assert_eq!(cv.pdb_filename.as_deref(), Some("old.pdb"));
+        assert_eq!(cv.debug_identifier.as_deref(), Some("663236F03"));
+    }
+}
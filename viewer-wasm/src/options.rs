@@ -0,0 +1,55 @@
+use serde::Deserialize;
+
+// Options controlling the optional (and potentially expensive) parts of parsing.
+// Every field defaults to the behavior of plain `parse_minidump`, so callers only
+// need to pass the options they want to change.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct ParseOptions {
+    pub compute_entropy: bool, // Shannon entropy for each captured memory region
+    pub classify_system_modules: bool, // Tag OS/system modules in the module list
+    pub include_exception_debug: bool, // `ExceptionData.debug`
+    pub include_exception_context_debug: bool, // `ExceptionData.context_debug`
+    pub allow_stack_scan: bool, // Guess frames by scanning stack memory when unwinding can't
+    pub size_units: SizeUnits, // Labels and divisor for formatted sizes
+    pub symbolicate_crashing_thread_only: bool, // Other threads get addresses and modules only
+    pub normalize_addresses: bool, // Also report code addresses relative to their module's base
+    pub frame_display: bool,   // Preformatted `StackFrame.display` text
+    pub merge_context_frame: bool, // Crashing thread: always start the frames with the context IP
+    pub detect_priority_inversion: bool, // Advisory `Overview.priority_inversion_candidates`
+    pub thread_context_crashing_only: bool, // `ThreadData.context` for the crashing thread only
+    pub thread_debug_crashing_only: bool, // `ThreadData.debug` for the crashing thread only
+    pub register_raw_bytes: bool, // Little-endian `raw_bytes` on every context register
+    pub module_code_crc32: bool, // `ModuleInfo.code_crc32` over captured executable memory
+    pub read_shadow_stack: bool, // `ExceptionData.shadow_stack` from the CET shadow stack
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeUnits {
+    Iec, // KiB/MiB/GiB, 1024-based
+    Si,  // KB/MB/GB, 1000-based
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            compute_entropy: false,
+            classify_system_modules: false,
+            include_exception_debug: true,
+            include_exception_context_debug: true,
+            allow_stack_scan: true,
+            size_units: SizeUnits::Iec,
+            symbolicate_crashing_thread_only: false,
+            normalize_addresses: false,
+            frame_display: false,
+            merge_context_frame: false,
+            detect_priority_inversion: false,
+            thread_context_crashing_only: false,
+            thread_debug_crashing_only: false,
+            register_raw_bytes: false,
+            module_code_crc32: false,
+            read_shadow_stack: false,
+        }
+    }
+}
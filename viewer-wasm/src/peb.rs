@@ -0,0 +1,146 @@
+use crate::common::{read_u32_le, read_u64_le};
+use crate::memory::memory_at;
+use minidump::system_info::{Cpu, Os};
+use minidump::{MinidumpSystemInfo, MinidumpThreadList, UnifiedMemoryList};
+
+// Upper bound on the environment block we copy when its size isn't recorded
+const MAX_ENVIRONMENT_BYTES: usize = 64 * 1024;
+
+// Offsets of the fields along the TEB -> PEB -> RTL_USER_PROCESS_PARAMETERS chain
+struct PebLayout {
+    pointer_size: usize,
+    teb_peb: usize,                 // TEB.ProcessEnvironmentBlock
+    peb_process_parameters: usize,  // PEB.ProcessParameters
+    params_command_line: usize,     // RTL_USER_PROCESS_PARAMETERS.CommandLine (UNICODE_STRING)
+    params_environment: usize,      // RTL_USER_PROCESS_PARAMETERS.Environment
+    params_environment_size: usize, // RTL_USER_PROCESS_PARAMETERS.EnvironmentSize (Vista+)
+}
+
+const PEB_LAYOUT_X86: PebLayout = PebLayout {
+    pointer_size: 4,
+    teb_peb: 0x30,
+    peb_process_parameters: 0x10,
+    params_command_line: 0x40,
+    params_environment: 0x48,
+    params_environment_size: 0x290,
+};
+
+const PEB_LAYOUT_AMD64: PebLayout = PebLayout {
+    pointer_size: 8,
+    teb_peb: 0x60,
+    peb_process_parameters: 0x20,
+    params_command_line: 0x70,
+    params_environment: 0x80,
+    params_environment_size: 0x3f0,
+};
+
+pub struct ProcessParameters {
+    pub command_line: Option<String>,
+    pub environment: Vec<String>,
+}
+
+// Best-effort read of the Windows process command line and environment, following the first
+// thread's TEB to the PEB's ProcessParameters. Each step needs the pointed-to memory to be in
+// the dump, which typically means a full-memory dump; anything missing yields None.
+pub fn read_process_parameters(
+    system: &MinidumpSystemInfo,
+    threads: &MinidumpThreadList,
+    memory: &UnifiedMemoryList,
+) -> Option<ProcessParameters> {
+    if system.os != Os::Windows {
+        return None;
+    }
+    let layout = match system.cpu {
+        Cpu::X86 => &PEB_LAYOUT_X86,
+        Cpu::X86_64 => &PEB_LAYOUT_AMD64,
+        _ => return None,
+    };
+
+    let teb = threads.threads.first()?.raw.teb;
+    let peb = read_pointer(memory, layout, teb.checked_add(layout.teb_peb as u64)?)?;
+    let params = read_pointer(
+        memory,
+        layout,
+        peb.checked_add(layout.peb_process_parameters as u64)?,
+    )?;
+
+    let command_line = read_unicode_string(
+        memory,
+        layout,
+        params.checked_add(layout.params_command_line as u64)?,
+    );
+    let environment = read_environment(memory, layout, params).unwrap_or_default();
+
+    if command_line.is_none() && environment.is_empty() {
+        return None;
+    }
+    Some(ProcessParameters {
+        command_line,
+        environment,
+    })
+}
+
+fn read_pointer(memory: &UnifiedMemoryList, layout: &PebLayout, address: u64) -> Option<u64> {
+    let bytes = memory_at(memory, address, layout.pointer_size)?;
+    match layout.pointer_size {
+        4 => read_u32_le(bytes, 0).map(u64::from),
+        _ => read_u64_le(bytes, 0),
+    }
+    .filter(|&pointer| pointer != 0)
+}
+
+// UNICODE_STRING: Length (bytes) and MaximumLength as u16, then the pointer-aligned Buffer
+fn read_unicode_string(
+    memory: &UnifiedMemoryList,
+    layout: &PebLayout,
+    address: u64,
+) -> Option<String> {
+    let header = memory_at(memory, address, 2)?;
+    let length = u16::from_le_bytes([*header.first()?, *header.get(1)?]) as usize;
+    let buffer = read_pointer(memory, layout, address + layout.pointer_size as u64)?;
+    let bytes = memory_at(memory, buffer, length)?;
+    Some(decode_utf16(bytes))
+}
+
+// The environment block is a run of NUL-terminated UTF-16 "NAME=value" strings ending with an
+// empty string
+fn read_environment(
+    memory: &UnifiedMemoryList,
+    layout: &PebLayout,
+    params: u64,
+) -> Option<Vec<String>> {
+    let block = read_pointer(
+        memory,
+        layout,
+        params.checked_add(layout.params_environment as u64)?,
+    )?;
+    let size = read_pointer(
+        memory,
+        layout,
+        params.checked_add(layout.params_environment_size as u64)?,
+    )
+    .map_or(MAX_ENVIRONMENT_BYTES, |size| {
+        (size as usize).min(MAX_ENVIRONMENT_BYTES)
+    });
+    let bytes = memory_at(memory, block, size)?;
+
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    Some(
+        units
+            .split(|&unit| unit == 0)
+            .take_while(|entry| !entry.is_empty())
+            .map(String::from_utf16_lossy)
+            .collect(),
+    )
+}
+
+fn decode_utf16(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
@@ -0,0 +1,195 @@
+use crate::common::SafeU64;
+use crate::threads::ThreadData;
+use serde::Serialize;
+use std::collections::HashSet;
+
+// A waiting thread and a lower-priority runnable thread that may hold what it waits on.
+// Advisory only: the shared "lock" is a register value both threads happen to hold.
+#[derive(Serialize)]
+pub struct PriorityInversionCandidate {
+    pub waiting_thread_id: u32,
+    pub waiting_priority: i32,
+    pub wait_reason: String,
+    pub holder_thread_id: u32,
+    pub holder_priority: i32,
+    pub lock_address: SafeU64,
+}
+
+// Register values below this are counts, flags and small integers rather than addresses
+const MIN_LOCK_ADDRESS: u64 = 0x10000;
+
+// Stack and frame pointers always differ between threads, and instruction pointers and the
+// link register point at code that any two threads may be running, so none of them can
+// identify a shared lock
+const NON_LOCK_REGISTERS: &[&str] = &[
+    "rsp", "rbp", "esp", "ebp", "sp", "fp", "x29", "lr", "x30", "rip", "eip", "pc",
+];
+
+// Best-effort: pair every thread with a wait_reason with each runnable (not waiting, not
+// exited) thread of lower priority that holds one of the same address-like values in a
+// general-purpose register. All threads share the process priority class, so only the
+// thread priorities are compared. Wait functions take the lock as an argument, so the waiter
+// usually still has its address in a register; the holder often does too while it works on
+// the protected data. Threads without a context, e.g. those stripped by
+// `thread_context_crashing_only`, never match. wait_reason comes from symbolicated frames,
+// which the wasm build only has when symbols are supplied, so without them the list is empty.
+pub fn find_priority_inversions(threads: &[ThreadData]) -> Vec<PriorityInversionCandidate> {
+    let lock_values = |thread: &ThreadData| -> HashSet<u64> {
+        thread
+            .context
+            .iter()
+            .flat_map(|context| &context.general_purpose)
+            .filter(|register| register.valid)
+            .filter(|register| !NON_LOCK_REGISTERS.contains(&register.name.to_lowercase().as_str()))
+            .map(|register| register.value.raw_value())
+            .filter(|&value| value >= MIN_LOCK_ADDRESS)
+            .collect()
+    };
+    // Thread priorities are signed (THREAD_PRIORITY_LOWEST is -2) but stored as u32
+    let priority = |thread: &ThreadData| thread.priority as i32;
+
+    let mut candidates = Vec::new();
+    for waiter in threads {
+        let Some(wait_reason) = waiter.wait_reason.as_deref() else {
+            continue;
+        };
+        let waiter_values = lock_values(waiter);
+        if waiter_values.is_empty() {
+            continue;
+        }
+
+        for holder in threads {
+            let runnable = holder.wait_reason.is_none() && holder.exit_code.is_none();
+            if !runnable || priority(holder) >= priority(waiter) {
+                continue;
+            }
+            let mut shared: Vec<u64> = lock_values(holder)
+                .intersection(&waiter_values)
+                .copied()
+                .collect();
+            shared.sort_unstable();
+            candidates.extend(
+                shared
+                    .into_iter()
+                    .map(|address| PriorityInversionCandidate {
+                        waiting_thread_id: waiter.thread_id,
+                        waiting_priority: priority(waiter),
+                        wait_reason: wait_reason.to_string(),
+                        holder_thread_id: holder.thread_id,
+                        holder_priority: priority(holder),
+                        lock_address: address.into(),
+                    }),
+            );
+        }
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{RegisterValue, StructuredContext};
+    use crate::threads::StackUnwindingMethod;
+
+    const LOCK: u64 = 0x7ff6_0000_1000;
+
+    fn register(name: &str, value: u64) -> RegisterValue {
+        RegisterValue {
+            name: name.to_string(),
+            value: value.into(),
+            category: "general_purpose".to_string(),
+            valid: true,
+            raw_bytes: None,
+        }
+    }
+
+    // A thread with the given priority, wait reason and general-purpose registers
+    fn thread(
+        thread_id: u32,
+        priority: i32,
+        wait_reason: Option<&str>,
+        registers: &[(&str, u64)],
+    ) -> ThreadData {
+        ThreadData {
+            thread_id,
+            name: None,
+            suspend_count: 0,
+            priority_class: 0x20,
+            priority: priority as u32,
+            teb: 0.into(),
+            is_crashing: false,
+            synthetic: false,
+            exit_code: None,
+            exit_code_name: None,
+            creation_rank: None,
+            wait_reason: wait_reason.map(str::to_string),
+            stack_pointer_in_bounds: true,
+            stack: None,
+            context: Some(StructuredContext {
+                general_purpose: registers
+                    .iter()
+                    .map(|&(name, value)| register(name, value))
+                    .collect(),
+                instruction_pointer: Vec::new(),
+                segment: Vec::new(),
+                flags: Vec::new(),
+                debug: Vec::new(),
+                other: Vec::new(),
+                vector: Vec::new(),
+                fpu: None,
+                architecture: "Amd64".to_string(),
+            }),
+            context_suspect: false,
+            stack_frames: None,
+            debug: None,
+            stack_unwinding_method: StackUnwindingMethod::Ok,
+            unwind_truncated_reason: None,
+        }
+    }
+
+    const WAIT: Option<&str> = Some("critical section (RtlEnterCriticalSection)");
+
+    #[test]
+    fn pairs_a_waiter_with_lower_priority_holders_of_the_same_address() {
+        let threads = [
+            thread(1, 2, WAIT, &[("rcx", LOCK), ("rdx", 0x7ff6_0000_2000)]),
+            thread(2, -2, None, &[("rbx", LOCK), ("rdx", 0x7ff6_0000_3000)]),
+            // Same priority as the waiter: no inversion
+            thread(3, 2, None, &[("rbx", LOCK)]),
+            // Lower priority but shares nothing
+            thread(4, 0, None, &[("rbx", 0x7ff6_0000_4000)]),
+        ];
+        let candidates = find_priority_inversions(&threads);
+        assert_eq!(candidates.len(), 1);
+        let candidate = &candidates[0];
+        assert_eq!(
+            (candidate.waiting_thread_id, candidate.holder_thread_id),
+            (1, 2)
+        );
+        assert_eq!(
+            (candidate.waiting_priority, candidate.holder_priority),
+            (2, -2)
+        );
+        assert_eq!(candidate.wait_reason, WAIT.unwrap());
+        assert_eq!(candidate.lock_address.raw_value(), LOCK);
+    }
+
+    #[test]
+    fn skips_exited_and_waiting_holders_and_non_lock_registers() {
+        let mut exited = thread(2, 0, None, &[("rbx", LOCK)]);
+        exited.exit_code = Some(0);
+        let threads = [
+            thread(
+                1,
+                2,
+                WAIT,
+                &[("rcx", LOCK), ("rip", 0x7ff6_0000_5000), ("rdx", 0x20)],
+            ),
+            exited,
+            thread(3, 0, WAIT, &[("rbx", LOCK)]),
+            // Running the same code, with the same small value in a register
+            thread(4, 0, None, &[("rip", 0x7ff6_0000_5000), ("rdx", 0x20)]),
+        ];
+        assert!(find_priority_inversions(&threads).is_empty());
+    }
+}
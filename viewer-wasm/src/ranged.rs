@@ -0,0 +1,398 @@
+use crate::common::{read_u32_le, read_u64_le};
+use crate::errors::{Result, ViewerError};
+use async_trait::async_trait;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+// MINIDUMP_STREAM_TYPE values for the streams the compact copy rewrites
+const THREAD_LIST_STREAM: u32 = 3;
+const MEMORY64_LIST_STREAM: u32 = 9;
+
+// Layout of MINIDUMP_HEADER, MINIDUMP_DIRECTORY and friends, in bytes
+const HEADER_SIZE: usize = 32;
+const DIRECTORY_ENTRY_SIZE: usize = 12;
+const THREAD_SIZE: usize = 48;
+const THREAD_STACK_OFFSET: usize = 24; // MINIDUMP_THREAD.Stack (MINIDUMP_MEMORY_DESCRIPTOR)
+const MEMORY64_LIST_HEADER_SIZE: usize = 16;
+const MEMORY64_DESCRIPTOR_SIZE: usize = 16;
+
+// Dumps up to this size are fetched whole and parsed as usual
+const IN_MEMORY_DUMP_LIMIT: u64 = 512 * 1024 * 1024;
+
+// An RVA past any file we build; the minidump crate then fails to read the descriptor's
+// bytes and looks the stack up by address in the memory list instead (see the tests)
+const UNREADABLE_RVA: u32 = u32::MAX;
+
+// Where the dump's bytes come from: the JS callback, or a buffer in tests
+#[async_trait(?Send)]
+pub trait RangeSource {
+    async fn read(&self, offset: u64, length: usize) -> Result<Vec<u8>>;
+}
+
+// The contract of the JS callback: `fetch(offset: number, length: number)` returns (or
+// resolves to) a Uint8Array or ArrayBuffer
+#[async_trait(?Send)]
+impl RangeSource for js_sys::Function {
+    async fn read(&self, offset: u64, length: usize) -> Result<Vec<u8>> {
+        let value = self
+            .call2(
+                &JsValue::NULL,
+                &JsValue::from_f64(offset as f64),
+                &JsValue::from_f64(length as f64),
+            )
+            .map_err(|e| ViewerError::Callback(format!("{:?}", e)))?;
+        let value = match value.dyn_into::<js_sys::Promise>() {
+            Ok(promise) => JsFuture::from(promise)
+                .await
+                .map_err(|e| ViewerError::Callback(format!("{:?}", e)))?,
+            Err(value) => value,
+        };
+        Ok(js_sys::Uint8Array::new(&value).to_vec())
+    }
+}
+
+// Fetch `length` bytes at `offset` of the dump. A source that returns any other number of
+// bytes is a Callback error.
+async fn fetch_range(fetch: &dyn RangeSource, offset: u64, length: usize) -> Result<Vec<u8>> {
+    let bytes = fetch.read(offset, length).await?;
+    if bytes.len() != length {
+        return Err(ViewerError::Callback(format!(
+            "fetch returned {} bytes for the {} bytes at offset {}",
+            bytes.len(),
+            length,
+            offset
+        )));
+    }
+    Ok(bytes)
+}
+
+// The bytes to parse for a dump of `size` bytes read through `fetch`: the whole file when it
+// is small enough, else the compact copy from read_compact_dump
+pub async fn read_ranged_dump(size: u64, fetch: &dyn RangeSource) -> Result<Vec<u8>> {
+    if size <= IN_MEMORY_DUMP_LIMIT {
+        return fetch_range(fetch, 0, size as usize).await;
+    }
+    read_compact_dump(size, fetch).await
+}
+
+// Build a self-contained copy of a dump that is too big to hold in wasm memory, fetching
+// only what parsing needs: everything before the Memory64 list's data (header, directory and
+// every other stream), plus the parts of the memory data that hold thread stacks. A new
+// Memory64 list describing just those stack ranges goes right after the metadata, followed by
+// their bytes, and the directory is pointed at it, so threads still unwind; other memory
+// (heap, module images) is left out.
+//
+// Writers put the Memory64 data last, after all other streams; a dump with a stream past
+// the data's start is rejected rather than fetched whole. A dump without a Memory64 list has
+// no bulk memory to skip and is fetched as is.
+async fn read_compact_dump(size: u64, fetch: &dyn RangeSource) -> Result<Vec<u8>> {
+    let header = fetch_range(fetch, 0, HEADER_SIZE).await?;
+    if !header.starts_with(b"MDMP") {
+        return Err(ViewerError::MinidumpRead(
+            "no minidump signature found".to_string(),
+        ));
+    }
+    let stream_count = read_u32_le(&header, 8).unwrap_or(0) as usize;
+    let directory_rva = read_u32_le(&header, 12).unwrap_or(0) as u64;
+    let directory = fetch_range(
+        fetch,
+        directory_rva,
+        stream_count.saturating_mul(DIRECTORY_ENTRY_SIZE),
+    )
+    .await?;
+    // (index, stream type, data size, rva)
+    let entries: Vec<(usize, u32, u32, u32)> = directory
+        .chunks_exact(DIRECTORY_ENTRY_SIZE)
+        .enumerate()
+        .filter_map(|(index, entry)| {
+            Some((
+                index,
+                read_u32_le(entry, 0)?,
+                read_u32_le(entry, 4)?,
+                read_u32_le(entry, 8)?,
+            ))
+        })
+        .collect();
+
+    let Some(&(memory64_index, _, memory64_size, memory64_rva)) = entries
+        .iter()
+        .find(|&&(_, stream_type, _, _)| stream_type == MEMORY64_LIST_STREAM)
+    else {
+        let size = usize::try_from(size)
+            .map_err(|_| ViewerError::MinidumpRead("dump too large to read".to_string()))?;
+        return fetch_range(fetch, 0, size).await;
+    };
+    if (memory64_size as usize) < MEMORY64_LIST_HEADER_SIZE {
+        return Err(ViewerError::MinidumpRead(
+            "Memory64 list too small".to_string(),
+        ));
+    }
+    let list = fetch_range(fetch, memory64_rva as u64, memory64_size as usize).await?;
+    let range_count = read_u64_le(&list, 0).unwrap_or(0);
+    let base_rva = read_u64_le(&list, 8).unwrap_or(0);
+
+    let metadata_end = entries
+        .iter()
+        .map(|&(_, _, data_size, rva)| rva as u64 + data_size as u64)
+        .chain([directory_rva + directory.len() as u64])
+        .max()
+        .unwrap_or(0);
+    if metadata_end > base_rva {
+        return Err(ViewerError::MinidumpRead(
+            "streams after the Memory64 data; ranged reading needs them before it".to_string(),
+        ));
+    }
+    let metadata_size = usize::try_from(base_rva)
+        .map_err(|_| ViewerError::MinidumpRead("Memory64 data offset out of range".to_string()))?;
+    let mut dump = fetch_range(fetch, 0, metadata_size).await?;
+
+    // Thread stacks, with each stack descriptor pointed away from the data it described
+    let mut stacks: Vec<(u64, u64)> = Vec::new();
+    if let Some(&(_, _, _, rva)) = entries
+        .iter()
+        .find(|&&(_, stream_type, _, _)| stream_type == THREAD_LIST_STREAM)
+    {
+        let rva = rva as usize;
+        let thread_count = read_u32_le(&dump, rva).unwrap_or(0) as usize;
+        for index in 0..thread_count {
+            let stack = rva + 4 + index * THREAD_SIZE + THREAD_STACK_OFFSET;
+            let (Some(start), Some(stack_size), Some(_)) = (
+                read_u64_le(&dump, stack),
+                read_u32_le(&dump, stack + 8),
+                read_u32_le(&dump, stack + 12),
+            ) else {
+                break;
+            };
+            stacks.push((start, start.saturating_add(stack_size as u64)));
+            dump[stack + 12..stack + 16].copy_from_slice(&UNREADABLE_RVA.to_le_bytes());
+        }
+    }
+
+    // Parts of the memory data that overlap a stack: (address, size, offset in the file)
+    let mut pieces: Vec<(u64, u64, u64)> = Vec::new();
+    let mut data_offset = base_rva;
+    for descriptor in list[MEMORY64_LIST_HEADER_SIZE..]
+        .chunks_exact(MEMORY64_DESCRIPTOR_SIZE)
+        .take(range_count as usize)
+    {
+        let start = read_u64_le(descriptor, 0).unwrap_or(0);
+        let range_size = read_u64_le(descriptor, 8).unwrap_or(0);
+        let end = start.saturating_add(range_size);
+        for &(stack_start, stack_end) in &stacks {
+            let low = start.max(stack_start);
+            let high = end.min(stack_end);
+            if low < high {
+                pieces.push((low, high - low, data_offset + (low - start)));
+            }
+        }
+        data_offset = data_offset.saturating_add(range_size);
+    }
+    pieces.sort_unstable();
+    pieces.dedup();
+
+    // The new list, then the pieces' bytes in the same order
+    let list_rva = dump.len();
+    let list_size = MEMORY64_LIST_HEADER_SIZE + pieces.len() * MEMORY64_DESCRIPTOR_SIZE;
+    let data_rva = (list_rva + list_size) as u64;
+    let (Ok(list_rva_field), Ok(list_size_field)) =
+        (u32::try_from(list_rva), u32::try_from(list_size))
+    else {
+        return Err(ViewerError::MinidumpRead(
+            "metadata too large for a compact copy".to_string(),
+        ));
+    };
+    dump.extend_from_slice(&(pieces.len() as u64).to_le_bytes());
+    dump.extend_from_slice(&data_rva.to_le_bytes());
+    for &(address, piece_size, _) in &pieces {
+        dump.extend_from_slice(&address.to_le_bytes());
+        dump.extend_from_slice(&piece_size.to_le_bytes());
+    }
+    for &(_, piece_size, offset) in &pieces {
+        let piece_size = usize::try_from(piece_size)
+            .map_err(|_| ViewerError::MinidumpRead("stack range too large".to_string()))?;
+        dump.extend_from_slice(&fetch_range(fetch, offset, piece_size).await?);
+    }
+
+    let entry = directory_rva as usize + memory64_index * DIRECTORY_ENTRY_SIZE;
+    dump[entry + 4..entry + 8].copy_from_slice(&list_size_field.to_le_bytes());
+    dump[entry + 8..entry + 12].copy_from_slice(&list_rva_field.to_le_bytes());
+
+    Ok(dump)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        DumpBuilder, Exception, Module, PLATFORM_WIN32_NT, PROCESSOR_ARCHITECTURE_AMD64,
+        SystemInfo, Thread, amd64_context, block_on,
+    };
+    use crate::{Overview, ParseOptions, parse_overview};
+    use minidump::{Minidump, MinidumpMemory64List, MinidumpThreadList, UnifiedMemoryList};
+    use std::cell::RefCell;
+
+    const MODULE_BASE: u64 = 0x1_4000_0000;
+    const STACK_BASE: u64 = 0x10_0000;
+    const HEAP_BASE: u64 = 0x50_0000;
+
+    // A dump in memory, recording the ranges read from it
+    struct Buffer {
+        bytes: Vec<u8>,
+        reads: RefCell<Vec<(u64, usize)>>,
+    }
+
+    impl Buffer {
+        fn new(bytes: Vec<u8>) -> Self {
+            Buffer {
+                bytes,
+                reads: RefCell::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl RangeSource for Buffer {
+        async fn read(&self, offset: u64, length: usize) -> Result<Vec<u8>> {
+            self.reads.borrow_mut().push((offset, length));
+            let start = (offset as usize).min(self.bytes.len());
+            let end = start.saturating_add(length).min(self.bytes.len());
+            Ok(self.bytes[start..end].to_vec())
+        }
+    }
+
+    fn full_memory_dump() -> Vec<u8> {
+        let ip = MODULE_BASE + 0x1234;
+        let context = amd64_context(ip, STACK_BASE + 0x100, STACK_BASE + 0x200);
+        DumpBuilder::new()
+            .system_info(SystemInfo::new(
+                PROCESSOR_ARCHITECTURE_AMD64,
+                PLATFORM_WIN32_NT,
+            ))
+            .thread(Thread::new(
+                0x10,
+                context.clone(),
+                STACK_BASE,
+                vec![0x11; 0x1000],
+            ))
+            .module(Module::new(MODULE_BASE, 0x10000, "C:\\app\\app.exe"))
+            .exception(Exception::new(0x10, 0xC000_0005, ip, context))
+            .memory(HEAP_BASE, vec![0x22; 0x4000])
+            .memory64()
+            .build()
+    }
+
+    fn stack_bytes(bytes: &[u8]) -> Vec<(u32, Vec<u8>)> {
+        let dump = Minidump::read(bytes).unwrap();
+        let threads = dump.get_stream::<MinidumpThreadList>().unwrap();
+        let memory = dump.get_stream::<MinidumpMemory64List>().unwrap();
+        let memory = UnifiedMemoryList::Memory64(memory);
+        threads
+            .threads
+            .iter()
+            .map(|thread| {
+                let stack = thread.stack_memory(&memory).unwrap();
+                (thread.raw.thread_id, stack.bytes().to_vec())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fetches_small_dumps_whole() {
+        let original = full_memory_dump();
+        let source = Buffer::new(original.clone());
+        let bytes = block_on(read_ranged_dump(original.len() as u64, &source)).unwrap();
+        assert_eq!(bytes, original);
+        assert_eq!(*source.reads.borrow(), [(0, original.len())]);
+    }
+
+    #[test]
+    fn compact_copy_keeps_stacks_and_drops_other_memory() {
+        let original = full_memory_dump();
+        let source = Buffer::new(original.clone());
+        let compact = block_on(read_compact_dump(original.len() as u64, &source)).unwrap();
+        let new_list_size = MEMORY64_LIST_HEADER_SIZE + MEMORY64_DESCRIPTOR_SIZE;
+        assert_eq!(compact.len(), original.len() - 0x4000 + new_list_size);
+
+        // The heap bytes are never fetched
+        let heap_offset = original.len() as u64 - 0x4000;
+        assert!(
+            source
+                .reads
+                .borrow()
+                .iter()
+                .all(|&(offset, length)| offset + length as u64 <= heap_offset)
+        );
+
+        // Threads unwind the same: the stack found by address through UNREADABLE_RVA
+        let options = ParseOptions::default();
+        let expected = block_on(parse_overview(&original, &options)).unwrap();
+        let actual = block_on(parse_overview(&compact, &options)).unwrap();
+        // Each thread's `debug` text shows the rewritten RVA, nothing else may differ
+        let json = |value: &Overview| {
+            let mut json = serde_json::json!([
+                value.threads_data,
+                value.exception_info,
+                value.system_info,
+                value.modules_data,
+            ]);
+            for thread in json[0].as_array_mut().unwrap() {
+                thread["debug"] = serde_json::Value::Null;
+            }
+            json
+        };
+        assert_eq!(json(&actual), json(&expected));
+        assert_eq!(
+            actual.threads_data.unwrap()[0]
+                .stack
+                .as_ref()
+                .unwrap()
+                .stack_captured_bytes,
+            0x1000
+        );
+        assert_eq!(stack_bytes(&compact), stack_bytes(&original));
+    }
+
+    #[test]
+    fn compact_copy_keeps_more_stack_pieces_than_original_ranges() {
+        // Thread 0x20's stack lies inside thread 0x10's, so each of the two ranges overlaps
+        // both stacks: three distinct pieces for a list that had room for two
+        let context = amd64_context(MODULE_BASE, STACK_BASE + 0x900, STACK_BASE + 0xa00);
+        let original = DumpBuilder::new()
+            .system_info(SystemInfo::new(
+                PROCESSOR_ARCHITECTURE_AMD64,
+                PLATFORM_WIN32_NT,
+            ))
+            .thread(Thread::new(
+                0x10,
+                context.clone(),
+                STACK_BASE,
+                vec![0x11; 0x2000],
+            ))
+            .thread(Thread::new(
+                0x20,
+                context,
+                STACK_BASE + 0x800,
+                vec![0x22; 0x800],
+            ))
+            .memory64()
+            .build();
+        let source = Buffer::new(original.clone());
+        let compact = block_on(read_compact_dump(original.len() as u64, &source)).unwrap();
+
+        let dump = Minidump::read(compact.as_slice()).unwrap();
+        let memory = dump.get_stream::<MinidumpMemory64List>().unwrap();
+        let pieces: Vec<(u64, Vec<u8>)> = memory
+            .iter()
+            .map(|range| (range.base_address, range.bytes.to_vec()))
+            .collect();
+        assert_eq!(
+            pieces,
+            [
+                (STACK_BASE, vec![0x11; 0x2000]),
+                (STACK_BASE + 0x800, vec![0x11; 0x800]),
+                (STACK_BASE + 0x800, vec![0x22; 0x800]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use crate::crash::CrashSummary;
+use crate::exception::ExceptionData;
+use crate::modules::ModuleData;
+use crate::system_info::SystemInfoData;
+use crate::threads::ThreadData;
+use std::fmt::Write;
+
+// Assemble a shareable Markdown report: a header with the OS and crash reason, the crashing
+// thread's stack, and the module list
+pub fn build_report_markdown(
+    system: Option<&SystemInfoData>,
+    exception: Option<&ExceptionData>,
+    crash_summary: Option<&CrashSummary>,
+    threads: Option<&[ThreadData]>,
+    modules: Option<&ModuleData>,
+) -> String {
+    let mut out = String::new();
+    // Writing into a String can't fail, so the fmt::Results below are ignored
+    let _ = writeln!(out, "# Crash report\n");
+
+    let os = system.and_then(|s| {
+        let version = s.raw.as_ref().and_then(|r| r.os_version.as_deref());
+        match (s.os.as_deref(), version) {
+            (Some(os), Some(version)) => Some(format!("{} {}", os, version)),
+            (os, _) => os.map(str::to_string),
+        }
+    });
+    let _ = writeln!(out, "- **OS:** {}", os.as_deref().unwrap_or("unknown"));
+    let _ = writeln!(
+        out,
+        "- **Crash reason:** {}",
+        exception
+            .and_then(|e| e.crash_reason.as_deref())
+            .unwrap_or("unknown")
+    );
+    if let Some(address) = exception.and_then(|e| e.crash_address.as_ref()) {
+        let _ = writeln!(out, "- **Crash address:** {}", address.to_hex_string());
+    }
+
+    let crashing_thread = crash_summary
+        .and_then(|c| c.crashing_thread_index)
+        .and_then(|i| threads?.get(i));
+    if let Some(summary) = crash_summary {
+        let _ = write!(out, "- **Crashing thread:** {}", summary.crashing_thread_id);
+        if let Some(name) = &summary.crashing_thread_name {
+            let _ = write!(out, " ({})", escape(name));
+        }
+        let _ = writeln!(out);
+    }
+
+    let _ = writeln!(out, "\n## Crashing thread stack\n");
+    match crashing_thread.and_then(|t| t.stack_frames.as_ref()) {
+        Some(frames) if !frames.is_empty() => {
+            let _ = writeln!(out, "| # | Address | Module | Function | Trust |");
+            let _ = writeln!(out, "|---|---------|--------|----------|-------|");
+            for (index, frame) in frames.iter().enumerate() {
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} | {} | {} |",
+                    index,
+                    frame.instruction_address.to_hex_string(),
+                    frame.module_name.as_deref().map(escape).unwrap_or_default(),
+                    frame
+                        .function_name
+                        .as_deref()
+                        .map(escape)
+                        .unwrap_or_default(),
+                    frame.trust_level
+                );
+            }
+        }
+        _ => {
+            let _ = writeln!(out, "No stack available for the crashing thread.");
+        }
+    }
+
+    let _ = writeln!(out, "\n## Modules\n");
+    match modules {
+        Some(modules) if !modules.modules.is_empty() => {
+            let _ = writeln!(out, "| Name | Base | Size | Version |");
+            let _ = writeln!(out, "|------|------|------|---------|");
+            for module in &modules.modules {
+                let version = module
+                    .version_info
+                    .as_ref()
+                    .and_then(|v| v.file_version.as_deref())
+                    .unwrap_or_default();
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {:#x} | {} |",
+                    escape(&module.name),
+                    module.base_of_image,
+                    module.size_of_image,
+                    version
+                );
+            }
+        }
+        _ => {
+            let _ = writeln!(out, "No module list in this dump.");
+        }
+    }
+
+    out
+}
+
+// Keep table cells intact when names contain Markdown table syntax
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|")
+}
@@ -0,0 +1,242 @@
+use crate::modules::normalized_module_name;
+use crate::threads::{StackFrame, ThreadData};
+
+// Number of symbolicated frames that make up a crash signature
+const SIGNATURE_FRAME_COUNT: usize = 5;
+
+pub struct CrashSignature {
+    pub signature: String,
+    pub hash: String,
+}
+
+// Build a stable crash signature from the crashing thread's resolved frames.
+//
+// Normalization rules:
+// - frames without a function name are skipped
+// - the module is reduced to its lowercased basename (`C:\App\Foo.DLL` -> `foo.dll`),
+//   or `?` when the frame has no module
+// - `+0x...` offsets are removed from the function name and whitespace is collapsed
+// - the first `SIGNATURE_FRAME_COUNT` normalized `module!function` entries are joined with ` | `
+//
+// The hash is the 64-bit FNV-1a of the signature, formatted as 16 hex digits. Addresses never
+// enter the signature, so it is already stable across ASLR and `normalize_addresses` doesn't
+// change it; normalized addresses are for comparing unsymbolicated frames by hand.
+//
+// Only frames with function names count, and the wasm build resolves none on its own (it has
+// no symbol files to load), so the signature is None unless symbols are supplied.
+pub fn build_crash_signature(
+    threads: &[ThreadData],
+    crashing_thread_id: u32,
+) -> Option<CrashSignature> {
+    let thread = threads.iter().find(|t| t.thread_id == crashing_thread_id)?;
+    let frames = thread.stack_frames.as_ref()?;
+
+    let parts: Vec<String> = frames
+        .iter()
+        .filter_map(normalize_frame)
+        .take(SIGNATURE_FRAME_COUNT)
+        .collect();
+
+    if parts.is_empty() {
+        return None;
+    }
+
+    let signature = parts.join(" | ");
+    let hash = format!("{:016x}", fnv1a_64(signature.as_bytes()));
+
+    Some(CrashSignature { signature, hash })
+}
+
+fn normalize_frame(frame: &StackFrame) -> Option<String> {
+    let function = normalize_function_name(frame.function_name.as_deref()?);
+    if function.is_empty() {
+        return None;
+    }
+
+    let module = frame
+        .module_name
+        .as_deref()
+        .map(normalized_module_name)
+        .unwrap_or_else(|| "?".to_string());
+
+    Some(format!("{}!{}", module, function))
+}
+
+fn normalize_function_name(name: &str) -> String {
+    // Drop any trailing offset, e.g. "Foo::bar + 0x40" or "Foo::bar+0x40"
+    let name = match name.find("+0x").or_else(|| name.find("+ 0x")) {
+        Some(idx) => &name[..idx],
+        None => name,
+    };
+
+    name.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn fnv1a_64(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        DumpBuilder, Exception, Module as TestModule, PLATFORM_WIN32_NT,
+        PROCESSOR_ARCHITECTURE_AMD64, SystemInfo, Thread, amd64_context, block_on, put_u64,
+    };
+    use crate::{ParseOptions, parse_overview};
+
+    fn frame(module: Option<&str>, function: Option<&str>) -> StackFrame {
+        StackFrame {
+            instruction_address: 0x1000.into(),
+            raw_instruction_address: None,
+            trust_level: "cfi".to_string(),
+            module_name: module.map(str::to_string),
+            function_name: function.map(str::to_string),
+            symbol_source: None,
+            is_signal_frame: false,
+            normalized_address: None,
+            display: None,
+            is_fault_frame: false,
+        }
+    }
+
+    // Threads 1 and 2 as the parser reports them, for their frames to be replaced
+    fn threads() -> Vec<ThreadData> {
+        let context = amd64_context(0x1000, 0x10_0100, 0x10_0200);
+        let bytes = DumpBuilder::new()
+            .system_info(SystemInfo::new(
+                PROCESSOR_ARCHITECTURE_AMD64,
+                PLATFORM_WIN32_NT,
+            ))
+            .thread(Thread::new(1, context.clone(), 0x10_0000, vec![0; 0x400]))
+            .thread(Thread::new(2, context, 0x20_0000, vec![0; 0x400]))
+            .build();
+        let overview = block_on(parse_overview(&bytes, &ParseOptions::default())).unwrap();
+        overview.threads_data.unwrap()
+    }
+
+    #[test]
+    fn normalizes_function_names() {
+        assert_eq!(normalize_function_name("Foo::bar+0x40"), "Foo::bar");
+        assert_eq!(normalize_function_name("Foo::bar + 0x40"), "Foo::bar");
+        assert_eq!(
+            normalize_function_name("  std::vector<int,\t std::allocator<int> >::at "),
+            "std::vector<int, std::allocator<int> >::at"
+        );
+    }
+
+    #[test]
+    fn normalizes_frames() {
+        assert_eq!(
+            normalize_frame(&frame(Some("C:\\App\\Foo.DLL"), Some("Foo::bar+0x40"))).as_deref(),
+            Some("foo.dll!Foo::bar")
+        );
+        assert_eq!(
+            normalize_frame(&frame(None, Some("main"))).as_deref(),
+            Some("?!main")
+        );
+        assert_eq!(normalize_frame(&frame(Some("foo.dll"), None)), None);
+        assert_eq!(
+            normalize_frame(&frame(Some("foo.dll"), Some(" +0x10"))),
+            None
+        );
+    }
+
+    #[test]
+    fn signs_the_first_symbolicated_frames_of_the_crashing_thread() {
+        let mut threads = threads();
+        let mut frames = vec![frame(Some("C:\\App\\App.exe"), None)];
+        frames.extend((0..7).map(|index| {
+            let function = format!("f{} + 0x{:x}", index, index * 0x10);
+            frame(Some("C:\\App\\App.exe"), Some(&function))
+        }));
+        threads[1].stack_frames = Some(frames);
+
+        assert!(build_crash_signature(&threads, 1).is_none()); // No frames with functions
+        assert!(build_crash_signature(&threads, 3).is_none()); // No such thread
+
+        let signature = build_crash_signature(&threads, 2).unwrap();
+        assert_eq!(
+            signature.signature,
+            "app.exe!f0 | app.exe!f1 | app.exe!f2 | app.exe!f3 | app.exe!f4"
+        );
+        assert_eq!(
+            signature.hash,
+            format!("{:016x}", fnv1a_64(signature.signature.as_bytes()))
+        );
+    }
+
+    // The same access violation three frames deep in app.exe, loaded at `module_base`, with
+    // the frames named as app.exe's symbols would name them
+    fn symbolicated_crash(module_base: u64) -> (Vec<ThreadData>, Option<String>) {
+        const STACK: u64 = 0x10_0000;
+        let mut stack = vec![0u8; 0x400];
+        put_u64(&mut stack, 0x200, STACK + 0x300);
+        put_u64(&mut stack, 0x208, module_base + 0x2010);
+        put_u64(&mut stack, 0x308, module_base + 0x3020);
+        let context = amd64_context(module_base + 0x1234, STACK + 0x100, STACK + 0x200);
+        let bytes = DumpBuilder::new()
+            .system_info(SystemInfo::new(
+                PROCESSOR_ARCHITECTURE_AMD64,
+                PLATFORM_WIN32_NT,
+            ))
+            .module(TestModule::new(module_base, 0x10000, "C:\\App\\App.exe"))
+            .thread(Thread::new(1, context.clone(), STACK, stack))
+            .exception(Exception::new(
+                1,
+                0xC000_0005,
+                module_base + 0x1234,
+                context,
+            ))
+            .build();
+        let overview = block_on(parse_overview(&bytes, &ParseOptions::default())).unwrap();
+        let mut threads = overview.threads_data.unwrap();
+
+        let symbols = [(0x1000, "crash_here"), (0x2000, "caller"), (0x3000, "main")];
+        for frame in threads[0].stack_frames.iter_mut().flatten() {
+            let offset = frame.instruction_address.raw_value() - module_base;
+            frame.function_name = symbols
+                .iter()
+                .rev()
+                .find(|&&(start, _)| start <= offset)
+                .map(|&(start, name)| format!("{} + 0x{:x}", name, offset - start));
+        }
+        (threads, overview.crash_signature)
+    }
+
+    #[test]
+    fn signs_two_dumps_of_the_same_crash_alike_across_module_bases() {
+        let (first, unsymbolicated) = symbolicated_crash(0x1_4000_0000);
+        let (second, _) = symbolicated_crash(0x7ff6_1230_0000);
+        assert_eq!(unsymbolicated, None);
+        assert_ne!(
+            first[0].stack_frames.as_ref().unwrap()[0]
+                .instruction_address
+                .raw_value(),
+            second[0].stack_frames.as_ref().unwrap()[0]
+                .instruction_address
+                .raw_value()
+        );
+
+        let first = build_crash_signature(&first, 1).unwrap();
+        let second = build_crash_signature(&second, 1).unwrap();
+        assert_eq!(
+            first.signature,
+            "app.exe!crash_here | app.exe!caller | app.exe!main"
+        );
+        assert_eq!(first.signature, second.signature);
+        assert_eq!(first.hash, second.hash);
+    }
+
+    #[test]
+    fn hashes_with_fnv1a_64() {
+        assert_eq!(fnv1a_64(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a_64(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a_64(b"foobar"), 0x85944171f73967e8);
+    }
+}
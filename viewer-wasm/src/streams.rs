@@ -0,0 +1,191 @@
+use crate::common::SafeU64;
+use minidump::Minidump;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+#[derive(Serialize)]
+pub struct DumpHeader {
+    // MINIDUMP_HEADER fields
+    pub signature: u32,
+    pub version: u32,
+    pub stream_count: u32,
+    pub stream_directory_rva: u32,
+    pub checksum: u32,
+    pub time_date_stamp: u32,
+    pub flags: SafeU64,
+}
+
+#[derive(Serialize)]
+pub struct StreamDirectoryEntry {
+    // MINIDUMP_DIRECTORY fields
+    pub stream_type: u32,
+    pub rva: u32,
+    pub data_size: u32,
+
+    // Derived from the stream type
+    pub name: Option<String>, // Canonical MINIDUMP_STREAM_TYPE name, None for unknown/vendor types
+    pub display_name: String, // e.g. "ThreadInfoListStream (type 17)"; the type is always decimal
+}
+
+// One of the streams the viewer knows how to show, with whether it was actually parsed or only
+// found in the directory (e.g. a corrupt stream, or a SystemInfo inferred from a context)
+#[derive(Serialize)]
+pub struct StreamPresence {
+    pub name: String,
+    pub stream_type: u32, // MINIDUMP_STREAM_TYPE
+    pub parsed: bool,
+}
+
+pub fn parse_dump_header(dump: &Minidump<'_, &[u8]>) -> DumpHeader {
+    let header = &dump.header;
+    DumpHeader {
+        signature: header.signature,
+        version: header.version,
+        stream_count: header.stream_count,
+        stream_directory_rva: header.stream_directory_rva,
+        checksum: header.checksum,
+        time_date_stamp: header.time_date_stamp,
+        flags: header.flags.into(),
+    }
+}
+
+pub fn parse_stream_directory(dump: &Minidump<'_, &[u8]>) -> Vec<StreamDirectoryEntry> {
+    dump.all_streams()
+        .map(|dir| {
+            let name = stream_type_name(dir.stream_type);
+            let display_name = match name {
+                Some(name) => format!("{} (type {})", name, dir.stream_type),
+                None => format!("Unknown (type {})", dir.stream_type),
+            };
+
+            StreamDirectoryEntry {
+                stream_type: dir.stream_type,
+                rva: dir.location.rva,
+                data_size: dir.location.data_size,
+                name: name.map(|n| n.to_string()),
+                display_name,
+            }
+        })
+        .collect()
+}
+
+// Stream type name lookup table, covering the standard MINIDUMP_STREAM_TYPE values
+// as well as the well-known Breakpad/Crashpad/Mozilla extensions
+static STREAM_TYPE_NAMES: OnceLock<HashMap<u32, &'static str>> = OnceLock::new();
+
+fn get_stream_type_names() -> &'static HashMap<u32, &'static str> {
+    STREAM_TYPE_NAMES.get_or_init(|| {
+        let mut map = HashMap::new();
+
+        // Standard Windows stream types
+        map.insert(0, "UnusedStream");
+        map.insert(1, "ReservedStream0");
+        map.insert(2, "ReservedStream1");
+        map.insert(3, "ThreadListStream");
+        map.insert(4, "ModuleListStream");
+        map.insert(5, "MemoryListStream");
+        map.insert(6, "ExceptionStream");
+        map.insert(7, "SystemInfoStream");
+        map.insert(8, "ThreadExListStream");
+        map.insert(9, "Memory64ListStream");
+        map.insert(10, "CommentStreamA");
+        map.insert(11, "CommentStreamW");
+        map.insert(12, "HandleDataStream");
+        map.insert(13, "FunctionTableStream");
+        map.insert(14, "UnloadedModuleListStream");
+        map.insert(15, "MiscInfoStream");
+        map.insert(16, "MemoryInfoListStream");
+        map.insert(17, "ThreadInfoListStream");
+        map.insert(18, "HandleOperationListStream");
+        map.insert(19, "TokenStream");
+        map.insert(20, "JavaScriptDataStream");
+        map.insert(21, "SystemMemoryInfoStream");
+        map.insert(22, "ProcessVmCountersStream");
+        map.insert(23, "IptTraceStream");
+        map.insert(24, "ThreadNamesStream");
+
+        // Windows CE stream types
+        map.insert(0x8000, "ceStreamNull");
+        map.insert(0x8001, "ceStreamSystemInfo");
+        map.insert(0x8002, "ceStreamException");
+        map.insert(0x8003, "ceStreamModuleList");
+        map.insert(0x8004, "ceStreamProcessList");
+        map.insert(0x8005, "ceStreamThreadList");
+        map.insert(0x8006, "ceStreamThreadContextList");
+        map.insert(0x8007, "ceStreamThreadCallStackList");
+        map.insert(0x8008, "ceStreamMemoryVirtualList");
+        map.insert(0x8009, "ceStreamMemoryPhysicalList");
+        map.insert(0x800a, "ceStreamBucketParameters");
+        map.insert(0x800b, "ceStreamProcessModuleMap");
+        map.insert(0x800c, "ceStreamDiagnosisList");
+        map.insert(0xffff, "LastReservedStream");
+
+        // Breakpad extensions
+        map.insert(0x47670001, "BreakpadInfoStream");
+        map.insert(0x47670002, "AssertionInfoStream");
+        map.insert(0x47670003, "LinuxCpuInfo");
+        map.insert(0x47670004, "LinuxProcStatus");
+        map.insert(0x47670005, "LinuxLsbRelease");
+        map.insert(0x47670006, "LinuxCmdLine");
+        map.insert(0x47670007, "LinuxEnviron");
+        map.insert(0x47670008, "LinuxAuxv");
+        map.insert(0x47670009, "LinuxMaps");
+        map.insert(0x4767000a, "LinuxDsoDebug");
+
+        // Crashpad extensions
+        map.insert(0x43500001, "CrashpadInfoStream");
+
+        // Chromium/Mozilla extensions
+        map.insert(0x4b6b0002, "StabilityReportStream");
+        map.insert(0x4d7a0001, "MozMacosCrashInfoStream");
+        map.insert(0x4d7a0002, "MozMacosBootargsStream");
+        map.insert(0x4d7a0003, "MozLinuxLimits");
+        map.insert(0x4d7a0004, "MozSoftErrors");
+
+        map
+    })
+}
+
+pub fn stream_type_name(stream_type: u32) -> Option<&'static str> {
+    get_stream_type_names().get(&stream_type).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::DumpBuilder;
+
+    #[test]
+    fn names_known_unparsed_and_vendor_stream_types() {
+        let bytes = DumpBuilder::new()
+            .stream(19, vec![0; 16]) // TokenStream
+            .stream(0x4767_0001, vec![0; 12]) // Breakpad's BreakpadInfoStream
+            .stream(0x1234_0001, vec![0; 4])
+            .build();
+        // Listed in stream type order
+        let dump = Minidump::read(bytes.as_slice()).unwrap();
+        let entries: Vec<(u32, Option<String>, String)> = parse_stream_directory(&dump)
+            .into_iter()
+            .map(|entry| (entry.stream_type, entry.name, entry.display_name))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                (
+                    19,
+                    Some("TokenStream".to_string()),
+                    "TokenStream (type 19)".to_string()
+                ),
+                (0x1234_0001, None, "Unknown (type 305397761)".to_string()),
+                (
+                    0x4767_0001,
+                    Some("BreakpadInfoStream".to_string()),
+                    "BreakpadInfoStream (type 1197932545)".to_string()
+                ),
+            ]
+        );
+        assert_eq!(stream_type_name(17), Some("ThreadInfoListStream"));
+        assert_eq!(stream_type_name(25), None);
+    }
+}
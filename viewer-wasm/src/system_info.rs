@@ -1,5 +1,7 @@
-use crate::common::debug_output;
-use minidump::MinidumpSystemInfo;
+use crate::common::{debug_output, read_u32_le};
+use minidump::format::MINIDUMP_LOCATION_DESCRIPTOR;
+use minidump::system_info::Os;
+use minidump::{Endian, MinidumpStream, MinidumpSystemInfo};
 use serde::Serialize;
 
 #[derive(Serialize)]
@@ -27,14 +29,27 @@ pub struct SystemInfoRaw {
 #[derive(Serialize)]
 pub struct SystemInfoData {
     pub os: Option<String>,
+    pub os_display: String, // `os` for display, marked " (Wine)" for Wine/Proton dumps
+    pub wine: bool,         // See is_wine_dump
+    pub os_release_name: Option<String>, // Windows only, see windows_release_name
     pub cpu_info: Option<String>,
     pub raw: Option<SystemInfoRaw>,
     pub debug: Option<String>, // Raw debug output
 }
 
-pub fn parse_system_info(system: &MinidumpSystemInfo) -> SystemInfoData {
+// Wine and Proton report the Windows version they emulate, so `wine` (from the module
+// list) is the only way to tell their dumps apart from real Windows ones
+pub fn parse_system_info(system: &MinidumpSystemInfo, wine: bool) -> SystemInfoData {
+    let os = format!("{:?}", system.os);
     SystemInfoData {
-        os: Some(format!("{:?}", system.os)),
+        os_display: if wine {
+            format!("{} (Wine)", os)
+        } else {
+            os.clone()
+        },
+        wine,
+        os_release_name: (system.os == Os::Windows).then(|| windows_release_name(system)),
+        os: Some(os),
         cpu_info: system.cpu_info().map(|c| format!("{:?}", c)),
         raw: Some(SystemInfoRaw {
             processor_architecture: Some(system.raw.processor_architecture),
@@ -59,3 +74,172 @@ pub fn parse_system_info(system: &MinidumpSystemInfo) -> SystemInfoData {
         debug: debug_output(system),
     }
 }
+
+// Windows release by build number: (build, client name, server name). Every Windows 10 and 11
+// release reports version 10.0, so the build is what tells them apart.
+const WINDOWS_RELEASES: &[(u32, &str, &str)] = &[
+    (7600, "Windows 7", "Windows Server 2008 R2"),
+    (7601, "Windows 7 SP1", "Windows Server 2008 R2 SP1"),
+    (9200, "Windows 8", "Windows Server 2012"),
+    (9600, "Windows 8.1", "Windows Server 2012 R2"),
+    (10240, "Windows 10 1507", "Windows 10 1507"),
+    (10586, "Windows 10 1511", "Windows 10 1511"),
+    (14393, "Windows 10 1607", "Windows Server 2016"),
+    (15063, "Windows 10 1703", "Windows 10 1703"),
+    (16299, "Windows 10 1709", "Windows Server 1709"),
+    (17134, "Windows 10 1803", "Windows Server 1803"),
+    (17763, "Windows 10 1809", "Windows Server 2019"),
+    (18362, "Windows 10 1903", "Windows Server 1903"),
+    (18363, "Windows 10 1909", "Windows Server 1909"),
+    (19041, "Windows 10 2004", "Windows Server 2004"),
+    (19042, "Windows 10 20H2", "Windows Server 20H2"),
+    (19043, "Windows 10 21H1", "Windows 10 21H1"),
+    (19044, "Windows 10 21H2", "Windows 10 21H2"),
+    (19045, "Windows 10 22H2", "Windows 10 22H2"),
+    (20348, "Windows Server 2022", "Windows Server 2022"),
+    (22000, "Windows 11 21H2", "Windows 11 21H2"),
+    (22621, "Windows 11 22H2", "Windows 11 22H2"),
+    (22631, "Windows 11 23H2", "Windows 11 23H2"),
+    (26100, "Windows 11 24H2", "Windows Server 2025"),
+];
+
+// VER_NT_WORKSTATION; domain controllers and servers report 2 and 3
+const PRODUCT_TYPE_WORKSTATION: u8 = 1;
+
+// The release a Windows build number belongs to, e.g. 19045 -> "Windows 10 22H2", picking
+// the server name when product_type says so. Unknown builds fall back to the numeric
+// "Windows major.minor.build".
+fn windows_release_name(system: &MinidumpSystemInfo) -> String {
+    let raw = &system.raw;
+    let release = WINDOWS_RELEASES
+        .iter()
+        .find(|&&(build, _, _)| build == raw.build_number)
+        .map(|&(_, client, server)| {
+            if raw.product_type == PRODUCT_TYPE_WORKSTATION {
+                client
+            } else {
+                server
+            }
+        });
+    match release {
+        Some(name) => name.to_string(),
+        None => format!(
+            "Windows {}.{}.{}",
+            raw.major_version, raw.minor_version, raw.build_number
+        ),
+    }
+}
+
+// Size of MINIDUMP_SYSTEM_INFO
+const SYSTEM_INFO_SIZE: usize = 56;
+
+// PROCESSOR_ARCHITECTURE_* values for the contexts we can recognize
+const PROCESSOR_ARCHITECTURE_INTEL: u16 = 0;
+const PROCESSOR_ARCHITECTURE_ARM: u16 = 5;
+const PROCESSOR_ARCHITECTURE_AMD64: u16 = 9;
+const PROCESSOR_ARCHITECTURE_ARM64: u16 = 12;
+
+// Platform id that maps to `Os::Unknown`; the OS can't be told from a context
+const PLATFORM_ID_UNKNOWN: u32 = 0xffff_ffff;
+
+// When the SystemInfo stream is missing or corrupt, infer the CPU from a thread context so
+// contexts can still be read and stacks unwound. Each context layout is recognized by its
+// size and the CPU bits of its context_flags. The OS stays unknown.
+pub fn infer_system_info(
+    dump_bytes: &[u8],
+    context: &MINIDUMP_LOCATION_DESCRIPTOR,
+) -> Option<MinidumpSystemInfo> {
+    let start = context.rva as usize;
+    let bytes = dump_bytes.get(start..start.checked_add(context.data_size as usize)?)?;
+
+    // (context size, offset of context_flags, CPU flag bit, architecture)
+    let layouts = [
+        (1232, 0x30, 0x0010_0000, PROCESSOR_ARCHITECTURE_AMD64),
+        (716, 0, 0x0001_0000, PROCESSOR_ARCHITECTURE_INTEL),
+        (912, 0, 0x0040_0000, PROCESSOR_ARCHITECTURE_ARM64),
+        (368, 0, 0x0020_0000, PROCESSOR_ARCHITECTURE_ARM), // Windows CONTEXT_ARM
+        (368, 0, 0x4000_0000, PROCESSOR_ARCHITECTURE_ARM), // Breakpad MD_CONTEXT_ARM
+    ];
+    let architecture = layouts
+        .iter()
+        .find(|&&(size, flags_offset, cpu_flag, _)| {
+            bytes.len() >= size
+                && read_u32_le(bytes, flags_offset).is_some_and(|flags| flags & cpu_flag != 0)
+        })
+        .map(|&(_, _, _, architecture)| architecture)?;
+
+    // Synthesize a MINIDUMP_SYSTEM_INFO and let the minidump crate parse it as usual
+    let mut raw = [0u8; SYSTEM_INFO_SIZE];
+    raw[0..2].copy_from_slice(&architecture.to_le_bytes());
+    raw[20..24].copy_from_slice(&PLATFORM_ID_UNKNOWN.to_le_bytes());
+    // Point csd_version_rva past the buffer so no service pack string is read
+    raw[24..28].copy_from_slice(&(SYSTEM_INFO_SIZE as u32).to_le_bytes());
+    MinidumpSystemInfo::read(&raw, &raw, Endian::Little, None).ok()
+}
+
+// CPU count next to the thread count, so the two can be shown together ("4 CPUs, 37 threads")
+#[derive(Serialize)]
+pub struct ProcessorThreadCounts {
+    pub processors: u8,
+    pub threads: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        DumpBuilder, Thread, amd64_context, arm64_context, block_on, x86_context,
+    };
+    use crate::{ParseOptions, parse_overview};
+    use minidump::system_info::Cpu;
+    use minidump::{Minidump, MinidumpThreadList};
+
+    // Infer from the context of the only thread of a dump without SystemInfo
+    fn infer_from_thread_context(context: Vec<u8>) -> Option<MinidumpSystemInfo> {
+        let bytes = DumpBuilder::new()
+            .thread(Thread::new(1, context, 0x10_0000, vec![0; 0x100]))
+            .build();
+        let dump = Minidump::read(bytes.as_slice()).unwrap();
+        let threads = dump.get_stream::<MinidumpThreadList>().unwrap();
+        infer_system_info(&bytes, &threads.threads[0].raw.thread_context)
+    }
+
+    #[test]
+    fn infers_the_cpu_from_a_context() {
+        let cases = [
+            (amd64_context(0x1000, 0x10_0080, 0), Cpu::X86_64),
+            (arm64_context(0x1000, 0x10_0080, 0, 0), Cpu::Arm64),
+            (x86_context(0x1000, 0x10_0080, 0), Cpu::X86),
+        ];
+        for (context, cpu) in cases {
+            let system = infer_from_thread_context(context).unwrap();
+            assert_eq!(system.cpu, cpu);
+            assert_eq!(system.os, Os::Unknown(PLATFORM_ID_UNKNOWN));
+        }
+
+        // Right size, no CPU bits; or too short for any layout
+        assert!(infer_from_thread_context(vec![0; 1232]).is_none());
+        assert!(infer_from_thread_context(vec![0xff; 64]).is_none());
+    }
+
+    #[test]
+    fn overview_marks_inferred_system_info() {
+        let bytes = DumpBuilder::new()
+            .thread(Thread::new(
+                1,
+                amd64_context(0x1000, 0x10_0080, 0),
+                0x10_0000,
+                vec![0; 0x100],
+            ))
+            .build();
+        let overview = block_on(parse_overview(&bytes, &ParseOptions::default())).unwrap();
+        assert!(overview.system_info_inferred);
+        let raw = overview.system_info.unwrap().raw.unwrap();
+        assert_eq!(
+            raw.processor_architecture,
+            Some(PROCESSOR_ARCHITECTURE_AMD64)
+        );
+        let thread = &overview.threads_data.unwrap()[0];
+        assert!(thread.context.is_some());
+    }
+}
@@ -0,0 +1,533 @@
+// Test-only minidump writer. Lays out MINIDUMP_HEADER, the stream directory and the common
+// streams in memory so tests build exactly the dump they need instead of shipping opaque
+// binary fixtures. Structure layouts are the ones in minidump_common::format, little-endian
+// only; anything rarer goes in through `DumpBuilder::stream` as raw bytes.
+
+// Each test uses only the parts of the builder its dump needs
+#![allow(dead_code)]
+
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread as StdThread};
+
+// MINIDUMP_SYSTEM_INFO.ProcessorArchitecture
+pub const PROCESSOR_ARCHITECTURE_INTEL: u16 = 0;
+pub const PROCESSOR_ARCHITECTURE_AMD64: u16 = 9;
+pub const PROCESSOR_ARCHITECTURE_ARM64: u16 = 12;
+
+// MINIDUMP_SYSTEM_INFO.PlatformId
+pub const PLATFORM_WIN32_NT: u32 = 2;
+pub const PLATFORM_MACOS: u32 = 0x8101;
+pub const PLATFORM_LINUX: u32 = 0x8201;
+
+// MINIDUMP_STREAM_TYPE values of the streams the builder writes itself
+const THREAD_LIST_STREAM: u32 = 3;
+const MODULE_LIST_STREAM: u32 = 4;
+const MEMORY_LIST_STREAM: u32 = 5;
+const EXCEPTION_STREAM: u32 = 6;
+const SYSTEM_INFO_STREAM: u32 = 7;
+const MEMORY64_LIST_STREAM: u32 = 9;
+const MEMORY_INFO_LIST_STREAM: u32 = 16;
+
+const HEADER_SIZE: usize = 32;
+const DIRECTORY_ENTRY_SIZE: usize = 12;
+const MINIDUMP_VERSION: u32 = 0xa793;
+
+// Sizes of CONTEXT_X86, CONTEXT_AMD64 and CONTEXT_ARM64, and their ContextFlags for
+// control + integer + floating point registers
+pub const X86_CONTEXT_SIZE: usize = 716;
+pub const AMD64_CONTEXT_SIZE: usize = 1232;
+pub const ARM64_CONTEXT_SIZE: usize = 912;
+const CONTEXT_X86_FULL: u32 = 0x0001_0007;
+const CONTEXT_AMD64_FULL: u32 = 0x0010_000b;
+const CONTEXT_ARM64_FULL: u32 = 0x0040_0007;
+
+pub struct SystemInfo {
+    pub processor_architecture: u16,
+    pub platform_id: u32,
+    pub major_version: u32,
+    pub minor_version: u32,
+    pub build_number: u32,
+    pub product_type: u8,
+    pub number_of_processors: u8,
+}
+
+impl SystemInfo {
+    pub fn new(processor_architecture: u16, platform_id: u32) -> Self {
+        SystemInfo {
+            processor_architecture,
+            platform_id,
+            major_version: 10,
+            minor_version: 0,
+            build_number: 19045,
+            product_type: 1,
+            number_of_processors: 4,
+        }
+    }
+}
+
+pub struct Thread {
+    pub id: u32,
+    pub teb: u64,
+    pub priority: u32,
+    pub context: Vec<u8>,
+    pub stack_base: u64,
+    pub stack: Vec<u8>, // Also written as a memory region at stack_base
+}
+
+impl Thread {
+    pub fn new(id: u32, context: Vec<u8>, stack_base: u64, stack: Vec<u8>) -> Self {
+        Thread {
+            id,
+            teb: 0x7ff0_0000_0000 + id as u64 * 0x1000,
+            priority: 0,
+            context,
+            stack_base,
+            stack,
+        }
+    }
+}
+
+pub struct Module {
+    pub base: u64,
+    pub size: u32,
+    pub name: String,
+    pub time_date_stamp: u32,
+    pub file_version: Option<(u32, u32)>, // VS_FIXEDFILEINFO dwFileVersionMS/LS
+    pub cv_record: Vec<u8>,
+}
+
+impl Module {
+    pub fn new(base: u64, size: u32, name: &str) -> Self {
+        Module {
+            base,
+            size,
+            name: name.to_string(),
+            time_date_stamp: 0,
+            file_version: None,
+            cv_record: Vec::new(),
+        }
+    }
+
+    // A CV_INFO_PDB70 ("RSDS") record naming `pdb`
+    pub fn with_pdb70(mut self, guid: [u8; 16], age: u32, pdb: &str) -> Self {
+        let mut record = b"RSDS".to_vec();
+        record.extend_from_slice(&guid);
+        record.extend_from_slice(&age.to_le_bytes());
+        record.extend_from_slice(pdb.as_bytes());
+        record.push(0);
+        self.cv_record = record;
+        self
+    }
+}
+
+pub struct Exception {
+    pub thread_id: u32,
+    pub code: u32,
+    pub flags: u32,
+    pub record: u64, // ExceptionRecord: address of a nested EXCEPTION_RECORD
+    pub address: u64,
+    pub parameters: Vec<u64>, // ExceptionInformation, at most 15
+    pub context: Vec<u8>,
+}
+
+impl Exception {
+    pub fn new(thread_id: u32, code: u32, address: u64, context: Vec<u8>) -> Self {
+        Exception {
+            thread_id,
+            code,
+            flags: 0,
+            record: 0,
+            address,
+            parameters: Vec::new(),
+            context,
+        }
+    }
+}
+
+pub struct MemoryInfo {
+    pub base: u64,
+    pub allocation_base: u64,
+    pub allocation_protect: u32,
+    pub size: u64,
+    pub state: u32,
+    pub protect: u32,
+    pub kind: u32, // MINIDUMP_MEMORY_INFO.Type
+}
+
+#[derive(Default)]
+pub struct DumpBuilder {
+    flags: u64,
+    time_date_stamp: u32,
+    system_info: Option<SystemInfo>,
+    threads: Vec<Thread>,
+    modules: Vec<Module>,
+    exception: Option<Exception>,
+    memory: Vec<(u64, Vec<u8>)>,
+    memory64: bool,
+    memory_info: Vec<MemoryInfo>,
+    streams: Vec<(u32, Vec<u8>)>,
+}
+
+impl DumpBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // MINIDUMP_HEADER.Flags, the MINIDUMP_TYPE the dump was written with
+    pub fn flags(mut self, flags: u64) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    pub fn time_date_stamp(mut self, time_date_stamp: u32) -> Self {
+        self.time_date_stamp = time_date_stamp;
+        self
+    }
+
+    pub fn system_info(mut self, system_info: SystemInfo) -> Self {
+        self.system_info = Some(system_info);
+        self
+    }
+
+    pub fn thread(mut self, thread: Thread) -> Self {
+        self.threads.push(thread);
+        self
+    }
+
+    pub fn module(mut self, module: Module) -> Self {
+        self.modules.push(module);
+        self
+    }
+
+    pub fn exception(mut self, exception: Exception) -> Self {
+        self.exception = Some(exception);
+        self
+    }
+
+    // A captured memory region besides the thread stacks
+    pub fn memory(mut self, address: u64, bytes: Vec<u8>) -> Self {
+        self.memory.push((address, bytes));
+        self
+    }
+
+    // Write memory (stacks included) as a Memory64 list with the data at the end of the
+    // file, like a full-memory dump, instead of a MemoryList
+    pub fn memory64(mut self) -> Self {
+        self.memory64 = true;
+        self
+    }
+
+    pub fn memory_info(mut self, memory_info: MemoryInfo) -> Self {
+        self.memory_info.push(memory_info);
+        self
+    }
+
+    // Any other stream, written as given
+    pub fn stream(mut self, stream_type: u32, bytes: Vec<u8>) -> Self {
+        self.streams.push((stream_type, bytes));
+        self
+    }
+
+    pub fn build(self) -> Vec<u8> {
+        let mut directory: Vec<(u32, u32, u32)> = Vec::new();
+        let stream_count = [
+            self.system_info.is_some(),
+            !self.threads.is_empty(),
+            !self.modules.is_empty(),
+            self.exception.is_some(),
+            !self.threads.is_empty() || !self.memory.is_empty(),
+            !self.memory_info.is_empty(),
+        ]
+        .iter()
+        .filter(|&&present| present)
+        .count()
+            + self.streams.len();
+
+        let mut out = vec![0u8; HEADER_SIZE + stream_count * DIRECTORY_ENTRY_SIZE];
+        out[0..4].copy_from_slice(b"MDMP");
+        put_u32(&mut out, 4, MINIDUMP_VERSION);
+        put_u32(&mut out, 8, stream_count as u32);
+        put_u32(&mut out, 12, HEADER_SIZE as u32);
+        put_u32(&mut out, 20, self.time_date_stamp);
+        put_u64(&mut out, 24, self.flags);
+
+        if let Some(system) = &self.system_info {
+            let csd_version = push_blob(&mut out, &minidump_string(""));
+            let mut raw = vec![0u8; 56];
+            put_u16(&mut raw, 0, system.processor_architecture);
+            put_u16(&mut raw, 2, 6); // ProcessorLevel
+            raw[6] = system.number_of_processors;
+            raw[7] = system.product_type;
+            put_u32(&mut raw, 8, system.major_version);
+            put_u32(&mut raw, 12, system.minor_version);
+            put_u32(&mut raw, 16, system.build_number);
+            put_u32(&mut raw, 20, system.platform_id);
+            put_u32(&mut raw, 24, csd_version);
+            directory.push(push_stream(&mut out, SYSTEM_INFO_STREAM, &raw));
+        }
+
+        // Thread stacks come first among the memory regions; in a Memory64 list their RVAs
+        // are only known once the data is written, so the descriptors are patched then
+        let regions: Vec<(u64, &[u8])> = self
+            .threads
+            .iter()
+            .map(|thread| (thread.stack_base, thread.stack.as_slice()))
+            .chain(
+                self.memory
+                    .iter()
+                    .map(|(address, bytes)| (*address, bytes.as_slice())),
+            )
+            .collect();
+        let mut region_rvas: Vec<u32> = Vec::new();
+        if !self.memory64 {
+            region_rvas = regions
+                .iter()
+                .map(|(_, bytes)| push_blob(&mut out, bytes))
+                .collect();
+        }
+
+        let mut stack_rva_fields = Vec::new();
+        if !self.threads.is_empty() {
+            let contexts: Vec<u32> = self
+                .threads
+                .iter()
+                .map(|thread| push_blob(&mut out, &thread.context))
+                .collect();
+            let mut raw = (self.threads.len() as u32).to_le_bytes().to_vec();
+            for (index, thread) in self.threads.iter().enumerate() {
+                let mut entry = vec![0u8; 48];
+                put_u32(&mut entry, 0, thread.id);
+                put_u32(&mut entry, 12, thread.priority);
+                put_u64(&mut entry, 16, thread.teb);
+                put_u64(&mut entry, 24, thread.stack_base);
+                put_u32(&mut entry, 32, thread.stack.len() as u32);
+                put_u32(&mut entry, 36, region_rvas.get(index).copied().unwrap_or(0));
+                put_u32(&mut entry, 40, thread.context.len() as u32);
+                put_u32(&mut entry, 44, contexts[index]);
+                raw.extend_from_slice(&entry);
+            }
+            let entry = push_stream(&mut out, THREAD_LIST_STREAM, &raw);
+            stack_rva_fields = (0..self.threads.len())
+                .map(|index| entry.2 as usize + 4 + index * 48 + 36)
+                .collect();
+            directory.push(entry);
+        }
+
+        if !self.modules.is_empty() {
+            let names: Vec<u32> = self
+                .modules
+                .iter()
+                .map(|module| push_blob(&mut out, &minidump_string(&module.name)))
+                .collect();
+            let cv_records: Vec<u32> = self
+                .modules
+                .iter()
+                .map(|module| match module.cv_record.is_empty() {
+                    true => 0,
+                    false => push_blob(&mut out, &module.cv_record),
+                })
+                .collect();
+            let mut raw = (self.modules.len() as u32).to_le_bytes().to_vec();
+            for (index, module) in self.modules.iter().enumerate() {
+                let mut entry = vec![0u8; 108];
+                put_u64(&mut entry, 0, module.base);
+                put_u32(&mut entry, 8, module.size);
+                put_u32(&mut entry, 16, module.time_date_stamp);
+                put_u32(&mut entry, 20, names[index]);
+                if let Some((ms, ls)) = module.file_version {
+                    put_u32(&mut entry, 24, 0xfeef_04bd); // VS_FIXEDFILEINFO.dwSignature
+                    put_u32(&mut entry, 28, 0x0001_0000); // dwStrucVersion
+                    put_u32(&mut entry, 32, ms);
+                    put_u32(&mut entry, 36, ls);
+                    put_u32(&mut entry, 40, ms);
+                    put_u32(&mut entry, 44, ls);
+                }
+                put_u32(&mut entry, 76, module.cv_record.len() as u32);
+                put_u32(&mut entry, 80, cv_records[index]);
+                raw.extend_from_slice(&entry);
+            }
+            directory.push(push_stream(&mut out, MODULE_LIST_STREAM, &raw));
+        }
+
+        if let Some(exception) = &self.exception {
+            let context = push_blob(&mut out, &exception.context);
+            let mut raw = vec![0u8; 168];
+            put_u32(&mut raw, 0, exception.thread_id);
+            put_u32(&mut raw, 8, exception.code);
+            put_u32(&mut raw, 12, exception.flags);
+            put_u64(&mut raw, 16, exception.record);
+            put_u64(&mut raw, 24, exception.address);
+            put_u32(&mut raw, 32, exception.parameters.len() as u32);
+            for (index, &parameter) in exception.parameters.iter().take(15).enumerate() {
+                put_u64(&mut raw, 40 + index * 8, parameter);
+            }
+            put_u32(&mut raw, 160, exception.context.len() as u32);
+            put_u32(&mut raw, 164, context);
+            directory.push(push_stream(&mut out, EXCEPTION_STREAM, &raw));
+        }
+
+        if !self.memory_info.is_empty() {
+            let mut raw = Vec::new();
+            raw.extend_from_slice(&16u32.to_le_bytes()); // SizeOfHeader
+            raw.extend_from_slice(&48u32.to_le_bytes()); // SizeOfEntry
+            raw.extend_from_slice(&(self.memory_info.len() as u64).to_le_bytes());
+            for info in &self.memory_info {
+                let mut entry = vec![0u8; 48];
+                put_u64(&mut entry, 0, info.base);
+                put_u64(&mut entry, 8, info.allocation_base);
+                put_u32(&mut entry, 16, info.allocation_protect);
+                put_u64(&mut entry, 24, info.size);
+                put_u32(&mut entry, 32, info.state);
+                put_u32(&mut entry, 36, info.protect);
+                put_u32(&mut entry, 40, info.kind);
+                raw.extend_from_slice(&entry);
+            }
+            directory.push(push_stream(&mut out, MEMORY_INFO_LIST_STREAM, &raw));
+        }
+
+        for (stream_type, bytes) in &self.streams {
+            directory.push(push_stream(&mut out, *stream_type, bytes));
+        }
+
+        // Memory goes last, so a Memory64 list's data runs to the end of the file
+        if !regions.is_empty() && !self.memory64 {
+            let mut raw = (regions.len() as u32).to_le_bytes().to_vec();
+            for ((address, bytes), rva) in regions.iter().zip(&region_rvas) {
+                raw.extend_from_slice(&address.to_le_bytes());
+                raw.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+                raw.extend_from_slice(&rva.to_le_bytes());
+            }
+            directory.push(push_stream(&mut out, MEMORY_LIST_STREAM, &raw));
+        } else if !regions.is_empty() {
+            let list_size = 16 + regions.len() * 16;
+            align(&mut out);
+            let base_rva = (out.len() + list_size) as u64;
+            let mut raw = (regions.len() as u64).to_le_bytes().to_vec();
+            raw.extend_from_slice(&base_rva.to_le_bytes());
+            for (address, bytes) in &regions {
+                raw.extend_from_slice(&address.to_le_bytes());
+                raw.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+            }
+            directory.push(push_stream(&mut out, MEMORY64_LIST_STREAM, &raw));
+            let mut rva = base_rva as u32;
+            for (index, (_, bytes)) in regions.iter().enumerate() {
+                if let Some(&field) = stack_rva_fields.get(index) {
+                    put_u32(&mut out, field, rva);
+                }
+                out.extend_from_slice(bytes);
+                rva += bytes.len() as u32;
+            }
+        }
+
+        for (index, (stream_type, size, rva)) in directory.into_iter().enumerate() {
+            let entry = HEADER_SIZE + index * DIRECTORY_ENTRY_SIZE;
+            put_u32(&mut out, entry, stream_type);
+            put_u32(&mut out, entry + 4, size);
+            put_u32(&mut out, entry + 8, rva);
+        }
+        out
+    }
+}
+
+// CONTEXT_AMD64 with the given instruction, stack and frame pointers
+pub fn amd64_context(rip: u64, rsp: u64, rbp: u64) -> Vec<u8> {
+    let mut context = vec![0u8; AMD64_CONTEXT_SIZE];
+    put_u32(&mut context, 48, CONTEXT_AMD64_FULL);
+    put_u64(&mut context, 152, rsp);
+    put_u64(&mut context, 160, rbp);
+    put_u64(&mut context, 248, rip);
+    context
+}
+
+// CONTEXT_ARM64 with the given program counter, stack pointer, frame pointer and link register
+pub fn arm64_context(pc: u64, sp: u64, fp: u64, lr: u64) -> Vec<u8> {
+    let mut context = vec![0u8; ARM64_CONTEXT_SIZE];
+    put_u32(&mut context, 0, CONTEXT_ARM64_FULL);
+    put_u64(&mut context, 8 + 29 * 8, fp);
+    put_u64(&mut context, 8 + 30 * 8, lr);
+    put_u64(&mut context, 256, sp);
+    put_u64(&mut context, 264, pc);
+    context
+}
+
+// CONTEXT_X86 with the given instruction, stack and frame pointers
+pub fn x86_context(eip: u32, esp: u32, ebp: u32) -> Vec<u8> {
+    let mut context = vec![0u8; X86_CONTEXT_SIZE];
+    put_u32(&mut context, 0, CONTEXT_X86_FULL);
+    put_u32(&mut context, 180, ebp);
+    put_u32(&mut context, 184, eip);
+    put_u32(&mut context, 196, esp);
+    context
+}
+
+// A MINIDUMP_STRING: byte length, then UTF-16LE without the terminator counted
+pub fn minidump_string(text: &str) -> Vec<u8> {
+    let units: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    let mut bytes = (units.len() as u32).to_le_bytes().to_vec();
+    bytes.extend_from_slice(&units);
+    bytes.extend_from_slice(&[0, 0]);
+    bytes
+}
+
+// Where the first stream of `stream_type` starts in a built dump, for raw streams that hold
+// RVAs into themselves (patch them after `build`)
+pub fn stream_rva(dump: &[u8], stream_type: u32) -> usize {
+    let count = u32::from_le_bytes(dump[8..12].try_into().unwrap()) as usize;
+    (0..count)
+        .map(|index| HEADER_SIZE + index * DIRECTORY_ENTRY_SIZE)
+        .find(|&entry| dump[entry..entry + 4] == stream_type.to_le_bytes())
+        .map(|entry| u32::from_le_bytes(dump[entry + 8..entry + 12].try_into().unwrap()) as usize)
+        .expect("stream not in dump")
+}
+
+pub fn put_u16(bytes: &mut [u8], offset: usize, value: u16) {
+    bytes[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+}
+
+pub fn put_u32(bytes: &mut [u8], offset: usize, value: u32) {
+    bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+pub fn put_u64(bytes: &mut [u8], offset: usize, value: u64) {
+    bytes[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+}
+
+fn align(out: &mut Vec<u8>) {
+    out.resize(out.len().next_multiple_of(8), 0);
+}
+
+fn push_blob(out: &mut Vec<u8>, bytes: &[u8]) -> u32 {
+    align(out);
+    let rva = out.len() as u32;
+    out.extend_from_slice(bytes);
+    rva
+}
+
+fn push_stream(out: &mut Vec<u8>, stream_type: u32, bytes: &[u8]) -> (u32, u32, u32) {
+    let rva = push_blob(out, bytes);
+    (stream_type, bytes.len() as u32, rva)
+}
+
+// Run a future to completion on the current thread. Nothing parsing does waits on real I/O,
+// so the futures are ready on the first poll or as soon as they are woken.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(StdThread);
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
@@ -1,33 +1,64 @@
-use crate::common::{SafeU64, debug_output};
+use crate::common::{SafeU64, debug_output, read_u64_le};
 use crate::context::{StructuredContext, parse_context_registers};
+use crate::memory::{MEM_COMMIT, is_executable, memory_at};
+use crate::modules::{
+    ModuleIndex, module_file_name, normalized_module_name, pe_executable_sections,
+};
+use crate::options::ParseOptions;
+use async_trait::async_trait;
+use minidump::format::MemoryProtection;
+use minidump::system_info::Os;
 use minidump::{
-    Minidump, MinidumpModuleList, MinidumpSystemInfo, MinidumpThreadList, MinidumpThreadNames,
-    Module,
+    Minidump, MinidumpContext, MinidumpException, MinidumpMemoryInfoList, MinidumpModuleList,
+    MinidumpRawContext, MinidumpSystemInfo, MinidumpThread, MinidumpThreadInfoList,
+    MinidumpThreadList, MinidumpThreadNames, Module, UnifiedMemory, UnifiedMemoryList,
 };
 use minidump_unwind::{
-    CallStack, FrameTrust, SystemInfo, symbols::debuginfo::DebugInfoSymbolProvider, walk_stack,
+    CallStack, FileError, FileKind, FillSymbolError, FrameSymbolizer, FrameTrust, FrameWalker,
+    SymbolProvider, SystemInfo, symbols::debuginfo::DebugInfoSymbolProvider, walk_stack,
 };
 use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::PathBuf;
 
 #[derive(Serialize, Debug, Clone, Copy)]
 pub enum StackUnwindingMethod {
-    Ok,       // Professional stack unwinding succeeded
-    Fallback, // Used basic context unwinding
-    Failed,   // Couldn't unwind at all
+    Ok,          // Professional stack unwinding succeeded
+    OkNoSymbols, // Unwound with walk_stack, but no frame got a function name
+    Fallback,    // Used basic context unwinding
+    Failed,      // Couldn't unwind at all
 }
 
 #[derive(Serialize)]
 pub struct StackInfo {
     pub start_address: SafeU64,
     pub memory_size: u32,
-    pub memory_data: Vec<u8>, // Raw stack memory bytes
+    pub memory_data: Vec<u8>,        // Raw stack memory bytes
+    pub stack_captured_bytes: u32,   // Bytes of the declared stack actually present in the dump
+    pub stack_coverage_percent: f32, // stack_captured_bytes relative to memory_size
+    // PAGE_GUARD page of the stack's reservation, see find_stack_guard_page. Needs the
+    // MemoryInfo stream; an overflowed stack has used its guard page up.
+    pub has_guard_page: bool,
+    pub guard_page_address: Option<SafeU64>,
 }
 
 #[derive(Serialize)]
 pub struct StackFrame {
     pub instruction_address: SafeU64,
+    // The same address with its arm64e pointer authentication bits, when the unwinder
+    // stripped some; see signed_instruction_address
+    pub raw_instruction_address: Option<SafeU64>,
     pub trust_level: String, // "context", "cfi", "frame_pointer", "scan"
     pub module_name: Option<String>, // From module list, not symbols
+    pub function_name: Option<String>, // From symbols, None if unsymbolicated
+    pub symbol_source: Option<String>, // Where function_name came from: "debuginfo" or "none"
+    pub is_signal_frame: bool, // Signal return trampoline; the frame below it is where the fault was
+    // instruction_address - module base, with `normalize_addresses`. Unlike the absolute
+    // address this is the same across runs of an ASLR/PIE binary.
+    pub normalized_address: Option<SafeU64>,
+    pub display: Option<String>, // "#N module!function+0x.. (file:line)", with `frame_display`
+    pub is_fault_frame: bool, // Crashing thread only: the code that faulted, see mark_fault_frame
 }
 
 #[derive(Serialize)]
@@ -37,94 +68,507 @@ pub struct ThreadData {
     pub suspend_count: u32,
     pub priority_class: u32,
     pub priority: u32,
-    pub teb: SafeU64, // Thread Environment Block address
+    pub teb: SafeU64,      // Thread Environment Block address
+    pub is_crashing: bool, // The thread named by the exception stream, see crash::crashing_thread_id
+    // Not in the thread list: rebuilt from the exception stream's context, see
+    // ThreadParseContext::synthesize_crashing_thread
+    pub synthetic: bool,
+    pub exit_code: Option<u32>, // From the thread info list, only for threads that had exited
+    pub exit_code_name: Option<String>, // e.g. "STATUS_ACCESS_VIOLATION", when recognized
+    pub creation_rank: Option<u32>, // 0 for the oldest thread, see creation_ranks
+    // What the thread is blocked in, e.g. "critical section (RtlEnterCriticalSection)", from
+    // its top symbolicated frames. None without symbols or when it isn't waiting.
+    pub wait_reason: Option<String>,
+    // false when the context's stack pointer is outside the captured stack, see
+    // stack_pointer_in_bounds. Always true without a context.
+    pub stack_pointer_in_bounds: bool,
     pub stack: Option<StackInfo>,
     pub context: Option<StructuredContext>,
+    // The context's instruction pointer is outside every module, so the context may belong to
+    // another thread or be garbage (or the thread was running JIT code); see context_suspect
+    pub context_suspect: bool,
     pub stack_frames: Option<Vec<StackFrame>>, // Stack trace from unwinding
     pub debug: Option<String>,                 // Debug output for this specific thread
     pub stack_unwinding_method: StackUnwindingMethod,
+    // Set when unwinding stopped early because the dump lacks the stack memory it needed,
+    // rather than at the thread's entry point, see unwind_truncated_reason
+    pub unwind_truncated_reason: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct StackFramesPage {
+    pub total: u32, // Frames in the whole stack, not just this page
+    pub start: u32,
+    pub frames: Vec<StackFrame>,
+}
+
+#[derive(Serialize)]
+pub struct ThreadStack {
+    pub thread_id: u32,
+    pub base_address: SafeU64,
+    pub size: u32,       // Declared stack size from MINIDUMP_THREAD
+    pub bytes: Vec<u8>,  // Captured stack bytes, at most MAX_THREAD_STACK_BYTES
+    pub truncated: bool, // Whether bytes is shorter than the declared size
 }
 
-// Helper function to parse threads into structured format (async version)
-pub async fn parse_threads_data_async<'a>(
+// Upper bound on the bytes returned by `read_thread_stack`
+pub const MAX_THREAD_STACK_BYTES: usize = 1024 * 1024;
+
+// Raw captured stack of one thread, or None if the thread's stack isn't in the dump
+pub fn read_thread_stack(
+    thread: &MinidumpThread,
+    memory: &UnifiedMemoryList,
+) -> Option<ThreadStack> {
+    let base = thread.raw.stack.start_of_memory_range;
+    let size = thread.raw.stack.memory.data_size;
+    let bytes = memory_at(memory, base, (size as usize).min(MAX_THREAD_STACK_BYTES))?;
+
+    Some(ThreadStack {
+        thread_id: thread.raw.thread_id,
+        base_address: base.into(),
+        size,
+        truncated: bytes.len() < size as usize,
+        bytes: bytes.to_vec(),
+    })
+}
+
+// Shared inputs for parsing the threads of one dump
+pub struct ThreadParseContext<'a> {
     threads: &'a MinidumpThreadList<'a>,
     system: Option<&'a MinidumpSystemInfo>,
     thread_names: Option<&'a MinidumpThreadNames>,
     modules: Option<&'a MinidumpModuleList>,
-    dump: &'a Minidump<'_, &[u8]>,
+    module_index: Option<ModuleIndex<'a>>,
+    memory: Option<&'a UnifiedMemoryList<'a>>, // As memory::load_memory found it
+    no_memory: UnifiedMemoryList<'a>,          // Stands in for `memory` when there is none
+    thread_infos: Option<MinidumpThreadInfoList>,
+    creation_ranks: HashMap<u32, u32>,
+    memory_info: Option<MinidumpMemoryInfoList<'a>>,
+    thread_count: u32,
+    crashing_thread_id: Option<u32>,
+    options: &'a ParseOptions,
+}
+
+impl<'a> ThreadParseContext<'a> {
+    pub fn new(
+        threads: &'a MinidumpThreadList<'a>,
+        system: Option<&'a MinidumpSystemInfo>,
+        thread_names: Option<&'a MinidumpThreadNames>,
+        modules: Option<&'a MinidumpModuleList>,
+        dump: &'a Minidump<'a, &'a [u8]>,
+        memory: Option<&'a UnifiedMemoryList<'a>>,
+        options: &'a ParseOptions,
+    ) -> Self {
+        let thread_infos = dump.get_stream::<MinidumpThreadInfoList>().ok();
+        ThreadParseContext {
+            threads,
+            system,
+            thread_names,
+            modules,
+            module_index: modules.map(ModuleIndex::new),
+            memory,
+            no_memory: UnifiedMemoryList::default(),
+            creation_ranks: thread_infos
+                .as_ref()
+                .map(creation_ranks)
+                .unwrap_or_default(),
+            thread_infos,
+            memory_info: dump.get_stream::<MinidumpMemoryInfoList>().ok(),
+            thread_count: threads.threads.len() as u32,
+            crashing_thread_id: None,
+            options,
+        }
+    }
+
+    // The thread the exception was raised on; used to mark it and, with
+    // `symbolicate_crashing_thread_only`, to decide which thread gets symbols
+    pub fn with_crashing_thread(mut self, crashing_thread_id: Option<u32>) -> Self {
+        self.crashing_thread_id = crashing_thread_id;
+        self
+    }
+
+    // The dump's memory, empty when it has none; a thread's own stack descriptor is still read
+    // through an empty list
+    fn memory(&self) -> &UnifiedMemoryList<'a> {
+        self.memory.unwrap_or(&self.no_memory)
+    }
+
+    fn symbolicate(&self, thread_id: u32) -> bool {
+        !self.options.symbolicate_crashing_thread_only || self.crashing_thread_id == Some(thread_id)
+    }
+
+    fn thread_name(&self, thread_id: u32) -> Option<String> {
+        self.thread_names
+            .and_then(|names| names.get_name(thread_id))
+            .map(|name| name.into_owned())
+    }
+
+    // A context whose instruction pointer isn't in any loaded module. Corrupt context
+    // descriptors can point at another thread's context or at unrelated bytes, which still
+    // decode as registers. Not judged without a module list.
+    fn context_suspect(&self, context: &MinidumpContext) -> bool {
+        self.module_index.as_ref().is_some_and(|index| {
+            index
+                .module_for_address(context.get_instruction_pointer())
+                .is_none()
+        })
+    }
+
+    // Exit status is only meaningful once the thread has exited
+    fn exit_code(&self, thread_id: u32) -> Option<u32> {
+        self.thread_infos
+            .as_ref()
+            .and_then(|infos| infos.get_thread_info(thread_id))
+            .filter(|info| info.raw.dump_flags & MINIDUMP_THREAD_INFO_EXITED_THREAD != 0)
+            .map(|info| info.raw.exit_status)
+    }
+
+    // The exception can name a thread missing from the thread list, e.g. one that exited
+    // before the list was written or a list the writer filtered. Build a minimal entry for it
+    // from the exception stream's own context so the crashing thread is always shown. There
+    // is no stack memory for it, so the only frame is the context's instruction pointer.
+    pub fn synthesize_crashing_thread(&self, exception: &MinidumpException) -> Option<ThreadData> {
+        let thread_id = self.crashing_thread_id?;
+        if self.threads.get_thread(thread_id).is_some() {
+            return None;
+        }
+
+        let raw_context = self.system.and_then(|s| exception.context(s, None));
+        let stack_frames = raw_context.as_deref().map(|context| {
+            let mut frames = Vec::new();
+            ensure_context_frame(&mut frames, context.get_instruction_pointer(), self);
+            mark_fault_frame(&mut frames, self.system.map(|s| s.os));
+            frames
+        });
+        let exit_code = self.exit_code(thread_id);
+
+        Some(ThreadData {
+            thread_id,
+            name: self.thread_name(thread_id),
+            suspend_count: 0,
+            priority_class: 0,
+            priority: 0,
+            teb: 0.into(),
+            is_crashing: true,
+            synthetic: true,
+            exit_code,
+            exit_code_name: exit_code.and_then(exit_code_name).map(str::to_string),
+            creation_rank: self.creation_ranks.get(&thread_id).copied(),
+            wait_reason: None,
+            stack_pointer_in_bounds: true, // No thread stack to compare against
+            stack: None,
+            context: raw_context
+                .as_deref()
+                .map(|context| parse_context_registers(context, self.options.register_raw_bytes)),
+            context_suspect: raw_context
+                .as_deref()
+                .is_some_and(|context| self.context_suspect(context)),
+            stack_unwinding_method: if stack_frames.is_some() {
+                StackUnwindingMethod::Fallback
+            } else {
+                StackUnwindingMethod::Failed
+            },
+            stack_frames,
+            debug: None,
+            unwind_truncated_reason: None,
+        })
+    }
+}
+
+// Helper function to parse threads into structured format (async version). `exception`,
+// when given, also supplies a synthesized entry for a crashing thread the list lacks.
+pub async fn parse_threads_data_async(
+    ctx: &ThreadParseContext<'_>,
+    exception: Option<&MinidumpException<'_>>,
 ) -> Vec<ThreadData> {
     let mut thread_data = Vec::new();
 
     // Process each thread with proper async stack unwinding
-    for thread in &threads.threads {
-        // Get basic stack information from raw thread data
-        let stack = if thread.raw.stack.start_of_memory_range != 0 {
-            Some(StackInfo {
-                start_address: thread.raw.stack.start_of_memory_range.into(),
-                memory_size: thread.raw.stack.memory.data_size,
-                memory_data: Vec::new(), // Will be empty for now to avoid memory access issues
-            })
+    for thread in &ctx.threads.threads {
+        thread_data.push(parse_thread_data_async(thread, ctx).await);
+    }
+    thread_data.extend(exception.and_then(|e| ctx.synthesize_crashing_thread(e)));
+
+    // Sort threads by TEB address (Thread Environment Block) to ensure consistent ordering
+    // from lowest to highest address, using the thread id to break ties (e.g. all-zero TEBs)
+    thread_data.sort_by_key(|thread| (thread.teb.raw_value(), thread.thread_id));
+
+    thread_data
+}
+
+// Parse a single thread, including stack unwinding
+pub async fn parse_thread_data_async(
+    thread: &MinidumpThread<'_>,
+    ctx: &ThreadParseContext<'_>,
+) -> ThreadData {
+    // Get basic stack information from raw thread data
+    let stack = if thread.raw.stack.start_of_memory_range != 0 {
+        let memory_size = thread.raw.stack.memory.data_size;
+        let stack_captured_bytes = thread
+            .stack_memory(ctx.memory())
+            .map(|m| (m.bytes().len() as u64).min(memory_size as u64) as u32)
+            .unwrap_or(0);
+        let stack_coverage_percent = if memory_size > 0 {
+            stack_captured_bytes as f32 / memory_size as f32 * 100.0
         } else {
-            None
+            0.0
         };
-
-        // Get CPU context if available
-        let context = system
-            .and_then(|s| thread.context(s, None))
-            .map(|c| parse_context_registers(&c));
-
-        // Get thread name if available
-        let name = thread_names
-            .and_then(|names| names.get_name(thread.raw.thread_id))
-            .map(|name| name.into_owned());
-
-        // Use proper async stack unwinding with minidump-unwind
-        let (stack_frames, unwinding_method) =
-            extract_stack_frames_async(thread, system, modules, dump, threads.threads.len() as u32)
-                .await;
-
-        thread_data.push(ThreadData {
-            thread_id: thread.raw.thread_id,
-            name,
-            suspend_count: thread.raw.suspend_count,
-            priority_class: thread.raw.priority_class,
-            priority: thread.raw.priority,
-            teb: thread.raw.teb.into(),
-            stack,
-            context,
-            stack_frames,
-            debug: debug_output(thread),
-            stack_unwinding_method: unwinding_method,
+        let guard_page = ctx.memory_info.as_ref().and_then(|memory_info| {
+            find_stack_guard_page(memory_info, thread.raw.stack.start_of_memory_range)
         });
+
+        Some(StackInfo {
+            start_address: thread.raw.stack.start_of_memory_range.into(),
+            memory_size,
+            memory_data: Vec::new(), // Will be empty for now to avoid memory access issues
+            stack_captured_bytes,
+            stack_coverage_percent,
+            has_guard_page: guard_page.is_some(),
+            guard_page_address: guard_page.map(Into::into),
+        })
+    } else {
+        None
+    };
+
+    // Get CPU context if available
+    let raw_context = ctx.system.and_then(|s| thread.context(s, None));
+    let context = raw_context
+        .as_deref()
+        .map(|context| parse_context_registers(context, ctx.options.register_raw_bytes));
+    let stack_pointer_in_bounds = raw_context
+        .as_deref()
+        .is_none_or(|context| stack_pointer_in_bounds(thread, context.get_stack_pointer()));
+
+    // Get thread name if available
+    let name = ctx.thread_name(thread.raw.thread_id);
+    let exit_code = ctx.exit_code(thread.raw.thread_id);
+
+    // Use proper async stack unwinding with minidump-unwind
+    let (mut stack_frames, unwinding_method, unwind_truncated_reason) =
+        extract_stack_frames_async(thread, ctx).await;
+    let is_crashing = ctx.crashing_thread_id == Some(thread.raw.thread_id);
+    if is_crashing
+        && ctx.options.merge_context_frame
+        && let Some(context) = raw_context.as_deref()
+    {
+        ensure_context_frame(
+            stack_frames.get_or_insert_with(Vec::new),
+            context.get_instruction_pointer(),
+            ctx,
+        );
+    }
+    if is_crashing && let Some(frames) = stack_frames.as_mut() {
+        mark_fault_frame(frames, ctx.system.map(|s| s.os));
     }
 
-    // Sort threads by TEB address (Thread Environment Block) to ensure consistent ordering
-    // from lowest to highest address
-    thread_data.sort_by_key(|thread| thread.teb.raw_value());
+    ThreadData {
+        thread_id: thread.raw.thread_id,
+        name,
+        suspend_count: thread.raw.suspend_count,
+        priority_class: thread.raw.priority_class,
+        priority: thread.raw.priority,
+        teb: thread.raw.teb.into(),
+        is_crashing,
+        synthetic: false,
+        exit_code,
+        exit_code_name: exit_code.and_then(exit_code_name).map(str::to_string),
+        creation_rank: ctx.creation_ranks.get(&thread.raw.thread_id).copied(),
+        wait_reason: stack_frames.as_deref().and_then(wait_reason),
+        stack_pointer_in_bounds,
+        stack,
+        // Dropped for the other threads on request to keep large thread lists small
+        context: context.filter(|_| is_crashing || !ctx.options.thread_context_crashing_only),
+        context_suspect: raw_context
+            .as_deref()
+            .is_some_and(|context| ctx.context_suspect(context)),
+        stack_frames,
+        debug: debug_output(thread)
+            .filter(|_| is_crashing || !ctx.options.thread_debug_crashing_only),
+        stack_unwinding_method: unwinding_method,
+        unwind_truncated_reason,
+    }
+}
 
-    thread_data
+// Whether `stack_pointer` lies in the thread's stack as declared by MINIDUMP_THREAD. A thread
+// with no captured stack has nothing for it to point into.
+fn stack_pointer_in_bounds(thread: &MinidumpThread, stack_pointer: u64) -> bool {
+    let start = thread.raw.stack.start_of_memory_range;
+    let size = thread.raw.stack.memory.data_size as u64;
+    start != 0 && (start..start.saturating_add(size)).contains(&stack_pointer)
+}
+
+// MINIDUMP_THREAD_INFO.DumpFlags: the thread had exited when the dump was written
+const MINIDUMP_THREAD_INFO_EXITED_THREAD: u32 = 0x4;
+
+// Common Windows exit codes: NTSTATUS values a process or thread dies with, plus values the
+// CRT and runtime use. Plain small integers are application-defined and left unnamed.
+const EXIT_CODE_NAMES: &[(u32, &str)] = &[
+    (0x0000_0000, "STATUS_SUCCESS"),
+    (0x0000_0103, "STILL_ACTIVE"),
+    (0x4001_0004, "DBG_TERMINATE_PROCESS"),
+    (0x8000_0003, "STATUS_BREAKPOINT"),
+    (0xC000_0005, "STATUS_ACCESS_VIOLATION"),
+    (0xC000_0006, "STATUS_IN_PAGE_ERROR"),
+    (0xC000_0017, "STATUS_NO_MEMORY"),
+    (0xC000_001D, "STATUS_ILLEGAL_INSTRUCTION"),
+    (0xC000_0025, "STATUS_NONCONTINUABLE_EXCEPTION"),
+    (0xC000_008E, "STATUS_FLOAT_DIVIDE_BY_ZERO"),
+    (0xC000_0094, "STATUS_INTEGER_DIVIDE_BY_ZERO"),
+    (0xC000_0096, "STATUS_PRIVILEGED_INSTRUCTION"),
+    (0xC000_00FD, "STATUS_STACK_OVERFLOW"),
+    (0xC000_0135, "STATUS_DLL_NOT_FOUND"),
+    (0xC000_0139, "STATUS_ENTRYPOINT_NOT_FOUND"),
+    (0xC000_013A, "STATUS_CONTROL_C_EXIT"),
+    (0xC000_0142, "STATUS_DLL_INIT_FAILED"),
+    (0xC000_0374, "STATUS_HEAP_CORRUPTION"),
+    (0xC000_0409, "STATUS_STACK_BUFFER_OVERRUN"),
+    (0xC000_0420, "STATUS_ASSERTION_FAILURE"),
+    (0xC000_0602, "STATUS_FAIL_FAST_EXCEPTION"),
+    (0xE06D_7363, "CPP_EH_EXCEPTION"),
+];
+
+// Thread id -> position in creation order (0 = created first) over the thread info list
+// entries with a create time, exited threads included. Threads created in the same tick rank
+// by ascending thread id. Entries with a zero create time weren't timed and get no rank.
+fn creation_ranks(thread_infos: &MinidumpThreadInfoList) -> HashMap<u32, u32> {
+    let mut created: Vec<(u64, u32)> = thread_infos
+        .thread_infos
+        .iter()
+        .filter(|info| info.raw.create_time != 0)
+        .map(|info| (info.raw.create_time, info.raw.thread_id))
+        .collect();
+    created.sort_unstable();
+    created
+        .into_iter()
+        .enumerate()
+        .map(|(rank, (_, thread_id))| (thread_id, rank as u32))
+        .collect()
+}
+
+fn exit_code_name(code: u32) -> Option<&'static str> {
+    EXIT_CODE_NAMES
+        .iter()
+        .find(|&&(value, _)| value == code)
+        .map(|&(_, name)| name)
+}
+
+// Number of top frames searched for a wait function; past that the thread is busy in
+// something that merely calls one
+const WAIT_FRAME_DEPTH: usize = 6;
+
+// Functions a blocked thread sits in, and the kind of wait they mean
+const WAIT_FUNCTIONS: &[(&str, &str)] = &[
+    // Windows
+    ("NtWaitForSingleObject", "object"),
+    ("NtWaitForMultipleObjects", "multiple objects"),
+    ("WaitForSingleObject", "object"),
+    ("WaitForSingleObjectEx", "object"),
+    ("WaitForMultipleObjects", "multiple objects"),
+    ("WaitForMultipleObjectsEx", "multiple objects"),
+    ("MsgWaitForMultipleObjectsEx", "messages or objects"),
+    ("NtWaitForAlertByThreadId", "alert"),
+    ("RtlEnterCriticalSection", "critical section"),
+    ("RtlpWaitOnCriticalSection", "critical section"),
+    ("RtlpWaitOnAddress", "address"),
+    ("WaitOnAddress", "address"),
+    ("RtlAcquireSRWLockExclusive", "SRW lock"),
+    ("RtlAcquireSRWLockShared", "SRW lock"),
+    ("RtlSleepConditionVariableCS", "condition variable"),
+    ("RtlSleepConditionVariableSRW", "condition variable"),
+    ("NtDelayExecution", "sleep"),
+    ("SleepEx", "sleep"),
+    ("NtRemoveIoCompletion", "I/O completion"),
+    ("GetQueuedCompletionStatus", "I/O completion"),
+    ("NtWaitForWorkViaWorkerFactory", "thread pool idle"),
+    // POSIX / Linux
+    ("pthread_mutex_lock", "mutex"),
+    ("__pthread_mutex_lock", "mutex"),
+    ("pthread_cond_wait", "condition variable"),
+    ("pthread_cond_timedwait", "condition variable"),
+    ("pthread_rwlock_rdlock", "rwlock"),
+    ("pthread_rwlock_wrlock", "rwlock"),
+    ("pthread_join", "thread join"),
+    ("__lll_lock_wait", "mutex"),
+    ("futex_wait", "futex"),
+    ("sem_wait", "semaphore"),
+    ("nanosleep", "sleep"),
+    ("clock_nanosleep", "sleep"),
+    ("epoll_wait", "I/O"),
+    ("poll", "I/O"),
+    ("select", "I/O"),
+    // macOS
+    ("__psynch_mutexwait", "mutex"),
+    ("__psynch_cvwait", "condition variable"),
+    ("__semwait_signal", "semaphore"),
+    ("mach_msg_trap", "Mach message"),
+    ("__workq_kernreturn", "thread pool idle"),
+];
+
+fn wait_reason(frames: &[StackFrame]) -> Option<String> {
+    frames
+        .iter()
+        .take(WAIT_FRAME_DEPTH)
+        .filter_map(|frame| frame.function_name.as_deref())
+        .find_map(|function| {
+            // Symbol names may carry a module prefix or an offset ("ntdll!Foo+0x14")
+            let name = function.rsplit('!').next().unwrap_or(function);
+            let name = name.split('+').next().unwrap_or(name).trim();
+            WAIT_FUNCTIONS
+                .iter()
+                .find(|&&(wait_function, _)| wait_function == name)
+                .map(|&(wait_function, kind)| format!("{} ({})", kind, wait_function))
+        })
+}
+
+// Ids of threads whose name contains `query`, or that have a frame in a module whose
+// normalized name contains the query's. Matching is case-insensitive, and a query given as a
+// path matches on its file name.
+pub fn find_matching_threads(threads: &[ThreadData], query: &str) -> Vec<u32> {
+    let name_query = query.to_lowercase();
+    let module_query = normalized_module_name(query);
+
+    threads
+        .iter()
+        .filter(|thread| {
+            thread
+                .name
+                .as_deref()
+                .is_some_and(|name| name.to_lowercase().contains(&name_query))
+                || thread.stack_frames.as_ref().is_some_and(|frames| {
+                    frames.iter().any(|frame| {
+                        frame.module_name.as_deref().is_some_and(|module| {
+                            normalized_module_name(module).contains(&module_query)
+                        })
+                    })
+                })
+        })
+        .map(|thread| thread.thread_id)
+        .collect()
 }
 
 // Extract stack frames using minidump-unwind's walk_stack function
-// Returns (stack_frames, unwinding_method)
-async fn extract_stack_frames_async<'a>(
-    thread: &'a minidump::MinidumpThread<'a>,
-    system: Option<&'a MinidumpSystemInfo>,
-    modules: Option<&'a MinidumpModuleList>,
-    dump: &'a Minidump<'_, &[u8]>,
-    thread_count: u32,
-) -> (Option<Vec<StackFrame>>, StackUnwindingMethod) {
+// Returns (stack_frames, unwinding_method, unwind_truncated_reason)
+async fn extract_stack_frames_async(
+    thread: &MinidumpThread<'_>,
+    ctx: &ThreadParseContext<'_>,
+) -> (
+    Option<Vec<StackFrame>>,
+    StackUnwindingMethod,
+    Option<String>,
+) {
+    let system = ctx.system;
+    let modules = ctx.modules;
     let system_info = match system {
         Some(s) => s,
-        None => return (None, StackUnwindingMethod::Failed),
+        None => return (None, StackUnwindingMethod::Failed, None),
     };
     let modules_list = match modules {
         Some(m) => m,
-        None => return (None, StackUnwindingMethod::Failed),
+        None => return (None, StackUnwindingMethod::Failed, None),
     };
-    let memory = match dump.get_memory() {
+    let memory = match ctx.memory {
         Some(mem) => mem,
-        None => return (None, StackUnwindingMethod::Failed),
+        None => return (None, StackUnwindingMethod::Failed, None),
     };
 
     // Check if the CPU architecture is supported by DebugInfoSymbolProvider
@@ -134,22 +578,31 @@ async fn extract_stack_frames_async<'a>(
 
     if !cpu_supported {
         // CPU architecture not supported by DebugInfoSymbolProvider, use fallback
-        let fallback_frames = fallback_context_unwinding(thread, system, modules);
-        return (fallback_frames, StackUnwindingMethod::Fallback);
+        let fallback_frames = fallback_context_unwinding(thread, ctx);
+        return (fallback_frames, StackUnwindingMethod::Fallback, None);
     }
 
-    // Create DebugInfoSymbolProvider for supported architectures
+    // Create DebugInfoSymbolProvider for supported architectures.
+    // Note: the provider only loads unwind info (e.g. ELF `.eh_frame`) from module files it can
+    // open on the local filesystem, and there is none under wasm, so no module is loaded. Its
+    // unwinder then applies framehop's frame-pointer fallback rule to every caller frame, and
+    // walk_stack reports those frames with `CallFrameInfo` trust all the same; where that rule
+    // fails, walk_stack falls back to its own frame-pointer and scan unwinding.
+    // Windows ARM64 PE unwind info (.pdata/.xdata) would go unused even if loaded: framehop's
+    // ARM64 PE unwinder (0.13) always returns `Aarch64Unsupported`.
     let symbol_provider = DebugInfoSymbolProvider::new(system_info, modules_list).await;
 
     // Get CPU context for this thread
     let context = match system.and_then(|s| thread.context(s, None)) {
         Some(ctx) => ctx,
         None => {
-            let fallback_frames = fallback_context_unwinding(thread, system, modules);
-            return (fallback_frames, StackUnwindingMethod::Fallback);
+            let fallback_frames = fallback_context_unwinding(thread, ctx);
+            return (fallback_frames, StackUnwindingMethod::Fallback, None);
         }
     };
 
+    let stack_pointer = context.get_stack_pointer();
+
     // Create CallStack to hold unwound frames
     let mut call_stack = CallStack::with_context(context.into_owned());
 
@@ -161,12 +614,21 @@ async fn extract_stack_frames_async<'a>(
         cpu: system_info.cpu,
         cpu_info: system_info.cpu_info().map(|s| s.into_owned()),
         cpu_microcode_version: None,
-        cpu_count: thread_count as usize,
+        cpu_count: ctx.thread_count as usize,
     };
 
     // Use walk_stack to perform professional stack unwinding
     let thread_idx = thread.raw.thread_id as usize;
-    let stack_memory = thread.stack_memory(&memory);
+    // A stack pointer outside the thread's captured stack means a corrupt context or a
+    // switched stack (fibers, signal alternate stacks); unwind from the memory region that
+    // does hold it, if any was captured
+    let stack_memory = if stack_pointer_in_bounds(thread, stack_pointer) {
+        thread.stack_memory(memory)
+    } else {
+        memory
+            .memory_at_address(stack_pointer)
+            .or_else(|| thread.stack_memory(memory))
+    };
 
     // Walk the stack with proper async handling
     walk_stack(
@@ -178,54 +640,542 @@ async fn extract_stack_frames_async<'a>(
         stack_memory,
         modules_list,
         &system_info_for_unwind,
-        &symbol_provider,
+        &SelectiveSymbolProvider {
+            inner: &symbol_provider,
+            symbolicate: ctx.symbolicate(thread.raw.thread_id),
+        },
     )
     .await;
 
+    let truncated_reason = call_stack
+        .frames
+        .last()
+        .and_then(|last| unwind_truncated_reason(last, stack_memory, memory));
+
     // Convert minidump-unwind stack frames to our format
     let frames: Vec<StackFrame> = call_stack
         .frames
         .iter()
-        .map(|frame| {
+        .enumerate()
+        .map(|(index, frame)| {
             let module_name = frame
                 .module
                 .as_ref()
                 .map(|module| module.code_file().to_string());
+            let display = ctx.options.frame_display.then(|| {
+                format_frame_display(
+                    index,
+                    frame.instruction,
+                    frame
+                        .module
+                        .as_ref()
+                        .map(|module| (module.code_file(), module.base_address())),
+                    frame.function_name.as_deref().zip(frame.function_base),
+                    frame.source_file_name.as_deref().zip(frame.source_line),
+                )
+            });
 
             StackFrame {
                 instruction_address: frame.instruction.into(),
+                raw_instruction_address: signed_instruction_address(
+                    &call_stack.frames,
+                    index,
+                    memory,
+                )
+                .map(Into::into),
                 trust_level: frame_trust_to_string(&frame.trust),
                 module_name,
+                function_name: frame.function_name.clone(),
+                symbol_source: Some(symbol_source(frame).to_string()),
+                is_signal_frame: frame
+                    .function_name
+                    .as_deref()
+                    .is_some_and(is_signal_trampoline),
+                normalized_address: frame
+                    .module
+                    .as_ref()
+                    .filter(|_| ctx.options.normalize_addresses)
+                    .map(|module| (frame.instruction - module.base_address()).into()),
+                display,
+                is_fault_frame: false,
             }
         })
         .collect();
 
     if frames.is_empty() {
         // Fallback to basic context unwinding if walk_stack produces no frames
-        let fallback_frames = fallback_context_unwinding(thread, system, modules);
-        (fallback_frames, StackUnwindingMethod::Fallback)
+        let fallback_frames = fallback_context_unwinding(thread, ctx);
+        (fallback_frames, StackUnwindingMethod::Fallback, None)
+    } else if frames.iter().any(|frame| frame.function_name.is_some()) {
+        (Some(frames), StackUnwindingMethod::Ok, truncated_reason)
     } else {
-        (Some(frames), StackUnwindingMethod::Ok)
+        (
+            Some(frames),
+            StackUnwindingMethod::OkNoSymbols,
+            truncated_reason,
+        )
+    }
+}
+
+// arm64e signs the return addresses it saves (in lr, then in the frame record at fp + 8) with
+// pointer authentication bits above the address, and the unwinder strips them before using
+// the caller's address. Recover the signed value from where the caller's address was read, the
+// callee's lr or frame record, and report it adjusted the way `instruction` is adjusted from
+// the return address. Only values that differ from the stripped one in their high bits count,
+// so nothing is reported when stripping changed nothing or for frames found by CFI elsewhere.
+fn signed_instruction_address(
+    frames: &[minidump_unwind::StackFrame],
+    index: usize,
+    memory: &UnifiedMemoryList,
+) -> Option<u64> {
+    let frame = frames.get(index)?;
+    let callee = frames.get(index.checked_sub(1)?)?;
+    if !matches!(
+        callee.context.raw,
+        MinidumpRawContext::Arm64(_) | MinidumpRawContext::OldArm64(_)
+    ) {
+        return None;
+    }
+
+    let link_register = callee.context.get_register("lr").filter(|_| index == 1);
+    let frame_record = callee
+        .context
+        .get_register("fp")
+        .and_then(|fp| memory_at(memory, fp.checked_add(8)?, 8))
+        .and_then(|bytes| read_u64_le(bytes, 0));
+
+    let return_address = frame.resume_address;
+    [link_register, frame_record]
+        .into_iter()
+        .flatten()
+        .find(|&signed| signed != return_address && (signed ^ return_address) as u32 == 0)
+        .map(|signed| signed.wrapping_sub(return_address.wrapping_sub(frame.instruction)))
+}
+
+// Upper bound on the memory info regions walked below a stack by find_stack_guard_page
+const MAX_STACK_GUARD_SEARCH_REGIONS: usize = 16;
+
+// A stack grows down through its reservation: committed pages, then a PAGE_GUARD page, then
+// reserved pages. Walk down from the region holding the captured stack, staying within the
+// same allocation, to the guard page and return its base address.
+fn find_stack_guard_page(memory_info: &MinidumpMemoryInfoList, stack_start: u64) -> Option<u64> {
+    let allocation_base = memory_info
+        .memory_info_at_address(stack_start)?
+        .raw
+        .allocation_base;
+    let mut address = stack_start;
+    for _ in 0..MAX_STACK_GUARD_SEARCH_REGIONS {
+        let info = memory_info.memory_info_at_address(address)?;
+        if info.raw.allocation_base != allocation_base {
+            return None;
+        }
+        if info.protection.contains(MemoryProtection::PAGE_GUARD) {
+            return Some(info.raw.base_address);
+        }
+        address = info.raw.base_address.checked_sub(1)?;
+    }
+    None
+}
+
+// Functions a thread starts in; a walk ending in one of them reached the real bottom of the
+// stack
+const THREAD_ENTRY_FUNCTIONS: &[&str] = &[
+    "RtlUserThreadStart",
+    "BaseThreadInitThunk",
+    "start_thread",
+    "thread_start",
+    "_pthread_start",
+    "_start",
+    "__libc_start_main",
+    "clone",
+    "__clone",
+    "clone3",
+];
+
+// How far past the end of the captured stack a caller frame may still be looked for: the
+// usual 8 MiB Linux main thread stack, well above the 1 MiB Windows default
+const MAX_UNCAPTURED_STACK_BYTES: u64 = 8 * 1024 * 1024;
+
+// Why the walk stopped before the thread's entry point when the dump is to blame: the next
+// caller would be read from above the stack the walk ran on (the return address slot past the
+// last frame's saved frame pointer, or its stack pointer without one), and no captured memory
+// holds that slot. A chain ending in a null frame pointer or in captured memory ended on its
+// own, and so did one whose last frame is a known thread entry point (needs symbols).
+fn unwind_truncated_reason(
+    last: &minidump_unwind::StackFrame,
+    stack: Option<UnifiedMemory>,
+    memory: &UnifiedMemoryList,
+) -> Option<String> {
+    let at_entry = last.function_name.as_deref().is_some_and(|function| {
+        let name = function.rsplit('!').next().unwrap_or(function);
+        THREAD_ENTRY_FUNCTIONS.contains(&name.trim())
+    });
+    if at_entry {
+        return None;
     }
+
+    let context = &last.context;
+    let frame_pointer = match context.raw {
+        MinidumpRawContext::Amd64(_) => Some("rbp"),
+        MinidumpRawContext::X86(_) => Some("ebp"),
+        MinidumpRawContext::Arm(_)
+        | MinidumpRawContext::Arm64(_)
+        | MinidumpRawContext::OldArm64(_) => Some("fp"),
+        _ => None,
+    }
+    .and_then(|register| context.get_register(register));
+    let next_read = match frame_pointer {
+        Some(0) => return None,
+        Some(frame_pointer) => frame_pointer.checked_add(context.register_size() as u64)?,
+        None => context.get_stack_pointer(),
+    };
+
+    // Callers live above their callees; a slot outside that window is a garbage frame
+    // pointer rather than memory the dump left out
+    let stack = stack?;
+    let stack_end = stack.base_address().saturating_add(stack.size());
+    let above_stack = stack.base_address() <= next_read
+        && next_read < stack_end.saturating_add(MAX_UNCAPTURED_STACK_BYTES);
+    (above_stack && memory.memory_at_address(next_read).is_none())
+        .then(|| "stack memory not captured".to_string())
 }
 
-// Fallback to basic context unwinding
+// Unwinds through `inner` but only resolves symbols when `symbolicate` is set, so threads left
+// unsymbolicated still get their frames (addresses and modules)
+struct SelectiveSymbolProvider<'p, P> {
+    inner: &'p P,
+    symbolicate: bool,
+}
+
+#[async_trait]
+impl<P: SymbolProvider + Sync> SymbolProvider for SelectiveSymbolProvider<'_, P> {
+    async fn fill_symbol(
+        &self,
+        module: &(dyn Module + Sync),
+        frame: &mut (dyn FrameSymbolizer + Send),
+    ) -> Result<(), FillSymbolError> {
+        if !self.symbolicate {
+            return Err(FillSymbolError {});
+        }
+        self.inner.fill_symbol(module, frame).await
+    }
+
+    async fn walk_frame(
+        &self,
+        module: &(dyn Module + Sync),
+        walker: &mut (dyn FrameWalker + Send),
+    ) -> Option<()> {
+        self.inner.walk_frame(module, walker).await
+    }
+
+    async fn get_file_path(
+        &self,
+        module: &(dyn Module + Sync),
+        file_kind: FileKind,
+    ) -> Result<PathBuf, FileError> {
+        self.inner.get_file_path(module, file_kind).await
+    }
+}
+
+// Fallback to basic context unwinding, or to a stack scan when the thread has no context
 fn fallback_context_unwinding(
-    thread: &minidump::MinidumpThread,
-    system: Option<&MinidumpSystemInfo>,
-    modules: Option<&MinidumpModuleList>,
+    thread: &MinidumpThread,
+    ctx: &ThreadParseContext,
 ) -> Option<Vec<StackFrame>> {
-    let context = system.and_then(|s| thread.context(s, None))?;
+    let Some(context) = ctx.system.and_then(|s| thread.context(s, None)) else {
+        return if ctx.options.allow_stack_scan {
+            fallback_stack_scan(thread, ctx)
+        } else {
+            None
+        };
+    };
     let instruction_pointer = context.get_instruction_pointer();
-    let module_name = modules.and_then(|mods| find_module_for_address(mods, instruction_pointer));
+    let module_name = ctx
+        .module_index
+        .as_ref()
+        .and_then(|index| find_module_for_address(index, instruction_pointer));
 
     Some(vec![StackFrame {
         instruction_address: instruction_pointer.into(),
+        raw_instruction_address: None,
         trust_level: frame_trust_to_string(&FrameTrust::Context),
         module_name,
+        function_name: None,
+        symbol_source: Some("none".to_string()),
+        is_signal_frame: false,
+        normalized_address: normalized_address(ctx, instruction_pointer),
+        display: fallback_frame_display(ctx, instruction_pointer),
+        is_fault_frame: false,
     }])
 }
 
+// Number of stack words examined by `fallback_stack_scan`
+const MAX_STACK_SCAN_WORDS: usize = 1024;
+
+// Without a context there is no instruction pointer to start from, so report the first word
+// on the captured stack that points into a module's code as a single candidate (scanned) frame
+fn fallback_stack_scan(
+    thread: &MinidumpThread,
+    ctx: &ThreadParseContext,
+) -> Option<Vec<StackFrame>> {
+    use minidump::system_info::Cpu;
+
+    let modules = ctx.module_index.as_ref()?;
+    let stack = thread.stack_memory(ctx.memory())?;
+    let word_size = match ctx.system.map(|s| s.cpu) {
+        Some(Cpu::X86 | Cpu::Ppc | Cpu::Sparc | Cpu::Arm | Cpu::Mips) => 4,
+        _ => 8,
+    };
+
+    stack
+        .bytes()
+        .chunks_exact(word_size)
+        .take(MAX_STACK_SCAN_WORDS)
+        .map(|word| match *word {
+            [a, b, c, d] => u32::from_le_bytes([a, b, c, d]) as u64,
+            _ => u64::from_le_bytes(word.try_into().unwrap_or_default()),
+        })
+        .filter(|&address| is_code_address(ctx, address))
+        .find_map(|address| {
+            let module_name = find_module_for_address(modules, address)?;
+            Some(vec![StackFrame {
+                instruction_address: address.into(),
+                raw_instruction_address: None,
+                trust_level: frame_trust_to_string(&FrameTrust::Scan),
+                module_name: Some(module_name),
+                function_name: None,
+                symbol_source: Some("none".to_string()),
+                is_signal_frame: false,
+                normalized_address: normalized_address(ctx, address),
+                display: fallback_frame_display(ctx, address),
+                is_fault_frame: false,
+            }])
+        })
+}
+
+// Whether `address` can be a return address: it is in committed executable memory per the
+// MemoryInfo stream, or else in an executable section of its PE module. Without either (no
+// MemoryInfo and an uncaptured or non-PE header) code can't be told from data, so any address
+// in a module image counts.
+fn is_code_address(ctx: &ThreadParseContext, address: u64) -> bool {
+    if let Some(memory_info) = &ctx.memory_info {
+        return memory_info
+            .memory_info_at_address(address)
+            .is_some_and(|info| {
+                info.raw.state == MEM_COMMIT && is_executable(info.raw.protection)
+            });
+    }
+    let Some(module) = ctx
+        .module_index
+        .as_ref()
+        .and_then(|index| index.module_for_address(address))
+    else {
+        return false;
+    };
+    match pe_executable_sections(module, ctx.memory()) {
+        Some(sections) => sections
+            .iter()
+            .any(|&(start, end)| (start..end).contains(&address)),
+        None => true,
+    }
+}
+
+// `address` relative to the module containing it, when `normalize_addresses` is set
+fn normalized_address(ctx: &ThreadParseContext, address: u64) -> Option<SafeU64> {
+    if !ctx.options.normalize_addresses {
+        return None;
+    }
+    let module = ctx.module_index.as_ref()?.module_for_address(address)?;
+    Some((address - module.base_address()).into())
+}
+
+// Make sure frame 0 is the context's instruction pointer, labeled "context", so the
+// register-derived top frame is shown even when unwinding dropped or replaced it
+fn ensure_context_frame(
+    frames: &mut Vec<StackFrame>,
+    instruction_pointer: u64,
+    ctx: &ThreadParseContext,
+) {
+    if frames.first().is_some_and(|frame| {
+        frame.trust_level == "context"
+            && frame.instruction_address.raw_value() == instruction_pointer
+    }) {
+        return;
+    }
+
+    frames.insert(
+        0,
+        StackFrame {
+            instruction_address: instruction_pointer.into(),
+            raw_instruction_address: None,
+            trust_level: frame_trust_to_string(&FrameTrust::Context),
+            module_name: ctx
+                .module_index
+                .as_ref()
+                .and_then(|index| find_module_for_address(index, instruction_pointer)),
+            function_name: None,
+            symbol_source: Some("none".to_string()),
+            is_signal_frame: false,
+            normalized_address: normalized_address(ctx, instruction_pointer),
+            display: fallback_frame_display(ctx, instruction_pointer),
+            is_fault_frame: false,
+        },
+    );
+    // Keep the "#N" numbering of preformatted frames in step with the new positions
+    for (index, frame) in frames.iter_mut().enumerate().skip(1) {
+        if let Some(display) = frame.display.as_mut()
+            && let Some((_, rest)) = display.split_once(' ')
+        {
+            *display = format!("#{} {}", index, rest);
+        }
+    }
+}
+
+// Exception dispatch, abort and throw machinery that sits above the faulting code
+const WINDOWS_HANDLER_FUNCTIONS: &[&str] = &[
+    "KiUserExceptionDispatcher",
+    "RtlDispatchException",
+    "RtlpExecuteHandlerForException",
+    "RtlpCallVectoredHandlers",
+    "RtlRaiseException",
+    "RtlReportCriticalFailure",
+    "RtlpReportHeapFailure",
+    "RaiseException",
+    "RaiseFailFastException",
+    "UnhandledExceptionFilter",
+    "__C_specific_handler",
+    "__CxxFrameHandler3",
+    "__CxxFrameHandler4",
+    "_CxxThrowException",
+    "__report_gsfailure",
+    "_invoke_watson",
+    "abort",
+    "terminate",
+    "std::terminate",
+];
+
+const POSIX_HANDLER_FUNCTIONS: &[&str] = &[
+    "raise",
+    "__GI_raise",
+    "gsignal",
+    "abort",
+    "__GI_abort",
+    "pthread_kill",
+    "__pthread_kill",
+    "__pthread_kill_implementation",
+    "__pthread_kill_internal",
+    "__assert_fail",
+    "__assert_fail_base",
+    "__assert_rtn",
+    "__libc_message",
+    "__fortify_fail",
+    "__stack_chk_fail",
+    "__cxa_throw",
+    "__cxa_rethrow",
+    "_Unwind_RaiseException",
+    "abort_with_payload",
+    "__abort_with_payload",
+    "std::terminate",
+];
+
+// Mark the crashing thread's fault frame. Frames above a signal trampoline are the signal
+// handler, so the search starts below it; from there, known dispatcher/abort/throw frames
+// for the OS are skipped and the first remaining frame is the fault. Needs symbols: with no
+// function names there is nothing to go by and no frame is marked.
+fn mark_fault_frame(frames: &mut [StackFrame], os: Option<Os>) {
+    if frames.iter().all(|frame| frame.function_name.is_none()) {
+        return;
+    }
+    let handlers = match os {
+        Some(Os::Windows) => WINDOWS_HANDLER_FUNCTIONS,
+        _ => POSIX_HANDLER_FUNCTIONS,
+    };
+    let start = frames
+        .iter()
+        .rposition(|frame| frame.is_signal_frame)
+        .map_or(0, |index| index + 1);
+
+    let fault = frames.iter_mut().skip(start).find(|frame| {
+        !frame.function_name.as_deref().is_some_and(|function| {
+            let name = function.split('+').next().unwrap_or(function).trim();
+            handlers.contains(&name)
+        })
+    });
+    if let Some(frame) = fault {
+        frame.is_fault_frame = true;
+    }
+}
+
+// One-line frame text in the style of minidump-stackwalk's text output:
+// "#N module!function+0x.. (file:line)", "#N module+0x.." without a symbol, or "#N 0x.."
+// outside any module. The module is shown by its basename; offsets are from the function
+// and module base respectively.
+fn format_frame_display(
+    index: usize,
+    address: u64,
+    module: Option<(Cow<'_, str>, u64)>,
+    function: Option<(&str, u64)>,
+    source: Option<(&str, u32)>,
+) -> String {
+    let mut display = format!("#{} ", index);
+    match (module, function) {
+        (module, Some((function, base))) => {
+            if let Some((code_file, _)) = &module {
+                display.push_str(module_file_name(code_file));
+                display.push('!');
+            }
+            display.push_str(function);
+            display.push_str(&format!("+0x{:x}", address.saturating_sub(base)));
+        }
+        (Some((code_file, base)), None) => {
+            display.push_str(module_file_name(&code_file));
+            display.push_str(&format!("+0x{:x}", address.saturating_sub(base)));
+        }
+        (None, None) => display.push_str(&format!("0x{:x}", address)),
+    }
+    if let Some((file, line)) = source {
+        display.push_str(&format!(" ({}:{})", file, line));
+    }
+    display
+}
+
+// Display text for the single frame the fallback paths produce
+fn fallback_frame_display(ctx: &ThreadParseContext, address: u64) -> Option<String> {
+    if !ctx.options.frame_display {
+        return None;
+    }
+    let module = ctx
+        .module_index
+        .as_ref()
+        .and_then(|index| index.module_for_address(address))
+        .map(|module| (module.code_file(), module.base_address()));
+    Some(format_frame_display(0, address, module, None, None))
+}
+
+// Provenance of a frame's symbol. DebugInfoSymbolProvider is currently the only provider
+// handed to walk_stack, so anything resolved came from module debug info.
+fn symbol_source(frame: &minidump_unwind::StackFrame) -> &'static str {
+    if frame.function_name.is_some() {
+        "debuginfo"
+    } else {
+        "none"
+    }
+}
+
+// Functions the kernel returns through after running a signal handler (Linux vDSO and libc,
+// macOS libsystem). Only recognizable when the frame has a symbol.
+const SIGNAL_TRAMPOLINES: &[&str] = &[
+    "__kernel_rt_sigreturn",
+    "__kernel_sigreturn",
+    "__restore_rt",
+    "__restore",
+    "_sigtramp",
+];
+
+fn is_signal_trampoline(function_name: &str) -> bool {
+    SIGNAL_TRAMPOLINES.contains(&function_name.trim())
+}
+
 // Convert FrameTrust enum to our string representation
 fn frame_trust_to_string(trust: &FrameTrust) -> String {
     match trust {
@@ -239,15 +1189,214 @@ fn frame_trust_to_string(trust: &FrameTrust) -> String {
     }
 }
 
-// Find module that contains the given address (used by fallback)
-fn find_module_for_address(modules: &MinidumpModuleList, address: u64) -> Option<String> {
-    for module in modules.iter() {
-        let base_address = module.raw.base_of_image;
-        let end_address = base_address + module.raw.size_of_image as u64;
+// Name of the module that contains the given address (used by fallback)
+fn find_module_for_address(modules: &ModuleIndex, address: u64) -> Option<String> {
+    modules
+        .module_for_address(address)
+        .map(|module| module.name.clone())
+}
 
-        if address >= base_address && address < end_address {
-            return Some(module.name.clone());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        DumpBuilder, Exception, MemoryInfo as TestMemoryInfo, Module as TestModule,
+        PLATFORM_WIN32_NT, PROCESSOR_ARCHITECTURE_AMD64, SystemInfo, Thread, amd64_context,
+        block_on, put_u16, put_u32, put_u64,
+    };
+    use crate::{ParseOptions, parse_overview};
+
+    const MODULE_BASE: u64 = 0x1_4000_0000;
+    const STACK_BASE: u64 = 0x10_0000;
+
+    fn threads_data(builder: DumpBuilder) -> Vec<ThreadData> {
+        let bytes = builder
+            .system_info(SystemInfo::new(
+                PROCESSOR_ARCHITECTURE_AMD64,
+                PLATFORM_WIN32_NT,
+            ))
+            .module(TestModule::new(MODULE_BASE, 0x10000, "C:\\app\\app.exe"))
+            .build();
+        let overview = block_on(parse_overview(&bytes, &ParseOptions::default())).unwrap();
+        overview.threads_data.unwrap()
+    }
+
+    fn idle_thread(id: u32, stack_base: u64) -> Thread {
+        let context = amd64_context(MODULE_BASE + 0x100, stack_base + 0x100, stack_base + 0x200);
+        Thread::new(id, context, stack_base, vec![0; 0x1000])
+    }
+
+    #[test]
+    fn synthesizes_a_crashing_thread_missing_from_the_thread_list() {
+        let crash_ip = MODULE_BASE + 0x1234;
+        let context = amd64_context(crash_ip, 0x30_0100, 0x30_0200);
+        let threads = threads_data(
+            DumpBuilder::new()
+                .thread(idle_thread(0x10, STACK_BASE))
+                .exception(Exception::new(0x30, 0xC000_0005, crash_ip, context)),
+        );
+
+        // The synthetic thread has no TEB, so it sorts first
+        assert_eq!(threads.len(), 2);
+        assert!(!threads[1].synthetic && !threads[1].is_crashing);
+        let thread = &threads[0];
+        assert_eq!(thread.thread_id, 0x30);
+        assert!(thread.synthetic && thread.is_crashing);
+        assert!(thread.stack.is_none());
+        assert!(thread.context.is_some());
+        let frames = thread.stack_frames.as_ref().unwrap();
+        assert_eq!(frames[0].instruction_address.raw_value(), crash_ip);
+        assert_eq!(frames[0].module_name.as_deref(), Some("C:\\app\\app.exe"));
+
+        // Nothing is synthesized when the thread list has the crashing thread
+        let context = amd64_context(crash_ip, STACK_BASE + 0x100, STACK_BASE + 0x200);
+        let threads = threads_data(
+            DumpBuilder::new()
+                .thread(idle_thread(0x10, STACK_BASE))
+                .exception(Exception::new(0x10, 0xC000_0005, crash_ip, context)),
+        );
+        assert_eq!(threads.len(), 1);
+        assert!(!threads[0].synthetic && threads[0].is_crashing);
+    }
+
+    #[test]
+    fn unwinds_from_the_region_holding_an_out_of_bounds_stack_pointer() {
+        // The context points into a switched stack: rbp there starts a frame pointer chain
+        // whose return address is in app.exe
+        const SWITCHED_STACK: u64 = 0x80_0000;
+        let return_address = MODULE_BASE + 0x2000;
+        let mut switched = vec![0u8; 0x1000];
+        put_u64(&mut switched, 0x208, return_address);
+        let context = amd64_context(
+            MODULE_BASE + 0x100,
+            SWITCHED_STACK + 0x100,
+            SWITCHED_STACK + 0x200,
+        );
+        let threads = threads_data(
+            DumpBuilder::new()
+                .thread(Thread::new(0x10, context, STACK_BASE, vec![0; 0x1000]))
+                .thread(idle_thread(0x20, 0x20_0000))
+                .memory(SWITCHED_STACK, switched),
+        );
+
+        assert!(!threads[0].stack_pointer_in_bounds);
+        assert!(threads[1].stack_pointer_in_bounds);
+        // Caller frames report the address of the call, one byte before the return address
+        let frames = threads[0].stack_frames.as_ref().unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(
+            frames[1].instruction_address.raw_value(),
+            return_address - 1
+        );
+        assert_eq!(frames[1].module_name.as_deref(), Some("C:\\app\\app.exe"));
+    }
+
+    // A thread whose frame pointer chain has one caller in app.exe, whose saved frame pointer
+    // is `caller_frame_pointer`
+    fn thread_with_one_caller(caller_frame_pointer: u64) -> Thread {
+        let mut stack = vec![0u8; 0x400];
+        put_u64(&mut stack, 0x200, caller_frame_pointer);
+        put_u64(&mut stack, 0x208, MODULE_BASE + 0x2000);
+        let context = amd64_context(MODULE_BASE + 0x100, STACK_BASE + 0x100, STACK_BASE + 0x200);
+        Thread::new(0x10, context, STACK_BASE, stack)
+    }
+
+    #[test]
+    fn reports_a_walk_cut_off_by_uncaptured_stack_memory() {
+        // The caller's frame is past the 0x400 captured bytes
+        let threads =
+            threads_data(DumpBuilder::new().thread(thread_with_one_caller(STACK_BASE + 0x800)));
+        assert_eq!(threads[0].stack_frames.as_ref().unwrap().len(), 2);
+        assert_eq!(
+            threads[0].unwind_truncated_reason.as_deref(),
+            Some("stack memory not captured")
+        );
+    }
+
+    #[test]
+    fn leaves_a_walk_ending_at_a_null_frame_pointer_untruncated() {
+        let threads = threads_data(DumpBuilder::new().thread(thread_with_one_caller(0)));
+        assert_eq!(threads[0].stack_frames.as_ref().unwrap().len(), 2);
+        assert_eq!(threads[0].unwind_truncated_reason, None);
+
+        // A saved frame pointer far from the stack is garbage, not missing memory
+        let threads =
+            threads_data(DumpBuilder::new().thread(thread_with_one_caller(0x7fff_0000_0000)));
+        assert_eq!(threads[0].unwind_truncated_reason, None);
+    }
+
+    const CODE_ADDRESS: u64 = MODULE_BASE + 0x1010;
+
+    // A thread without a context whose stack holds a pointer into app.exe's data section
+    // before a return address into its code
+    fn contextless_thread() -> Thread {
+        let mut stack = vec![0u8; 0x100];
+        put_u64(&mut stack, 0, MODULE_BASE + 0x3010);
+        put_u64(&mut stack, 8, CODE_ADDRESS);
+        Thread::new(0x10, Vec::new(), STACK_BASE, stack)
+    }
+
+    fn scanned_frames(threads: &[ThreadData]) -> Vec<(u64, &str)> {
+        threads[0]
+            .stack_frames
+            .iter()
+            .flatten()
+            .map(|frame| {
+                (
+                    frame.instruction_address.raw_value(),
+                    frame.trust_level.as_str(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn stack_scan_skips_data_pointers_in_non_executable_memory() {
+        let region = |base: u64, protect: u32| TestMemoryInfo {
+            base,
+            allocation_base: MODULE_BASE,
+            allocation_protect: 0x80, // PAGE_EXECUTE_WRITECOPY
+            size: 0x1000,
+            state: 0x1000, // MEM_COMMIT
+            protect,
+            kind: 0x100_0000, // MEM_IMAGE
+        };
+        let threads = threads_data(
+            DumpBuilder::new()
+                .thread(contextless_thread())
+                .memory_info(region(MODULE_BASE + 0x1000, 0x20)) // PAGE_EXECUTE_READ
+                .memory_info(region(MODULE_BASE + 0x3000, 0x04)), // PAGE_READWRITE
+        );
+        assert_eq!(scanned_frames(&threads), [(CODE_ADDRESS, "scan")]);
+    }
+
+    #[test]
+    fn stack_scan_skips_data_pointers_outside_pe_code_sections() {
+        // PE header with a .text section at 0x1000 and a .data section at 0x3000
+        let mut header = vec![0u8; 0x400];
+        header[..2].copy_from_slice(b"MZ");
+        put_u32(&mut header, 0x3c, 0x80);
+        header[0x80..0x84].copy_from_slice(b"PE\0\0");
+        put_u16(&mut header, 0x86, 2);
+        put_u16(&mut header, 0x94, 0xf0);
+        for (index, (address, characteristics)) in [(0x1000, 0x6000_0020), (0x3000, 0xc000_0040)]
+            .into_iter()
+            .enumerate()
+        {
+            let section = 0x188 + index * 40;
+            put_u32(&mut header, section + 8, 0x1000);
+            put_u32(&mut header, section + 12, address);
+            put_u32(&mut header, section + 36, characteristics);
         }
+        let threads = threads_data(
+            DumpBuilder::new()
+                .thread(contextless_thread())
+                .memory(MODULE_BASE, header),
+        );
+        assert_eq!(scanned_frames(&threads), [(CODE_ADDRESS, "scan")]);
+
+        // Without section information the data pointer is the best guess
+        let threads = threads_data(DumpBuilder::new().thread(contextless_thread()));
+        assert_eq!(scanned_frames(&threads), [(MODULE_BASE + 0x3010, "scan")]);
     }
-    None
 }
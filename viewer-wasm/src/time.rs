@@ -0,0 +1,180 @@
+use minidump::MinidumpMiscInfo;
+use serde::Serialize;
+
+// Every timestamp we emit is UTC ISO-8601 with a `Z` suffix. A local time is only reported
+// alongside it, with an explicit offset, when the dump records the writer's timezone.
+
+#[derive(Serialize)]
+pub struct DumpTime {
+    pub utc: String,                   // e.g. "2024-05-01T12:34:56Z"
+    pub local_time: Option<String>,    // e.g. "2024-05-01T08:34:56-04:00", from MiscInfo
+    pub timezone_name: Option<String>, // Standard or daylight name, whichever was active
+}
+
+// When the dump was written, from MINIDUMP_HEADER.TimeDateStamp. None if unset.
+pub fn build_dump_time(timestamp: u32, misc_info: Option<&MinidumpMiscInfo>) -> Option<DumpTime> {
+    if timestamp == 0 {
+        return None;
+    }
+    let timestamp = timestamp as i64;
+    let timezone = misc_info.and_then(active_timezone);
+
+    Some(DumpTime {
+        utc: format_utc(timestamp),
+        local_time: timezone
+            .as_ref()
+            .map(|(_, offset)| format_local(timestamp, *offset)),
+        timezone_name: timezone.and_then(|(name, _)| name),
+    })
+}
+
+// Seconds since the Unix epoch as "YYYY-MM-DDTHH:MM:SSZ"
+pub fn format_utc(timestamp: i64) -> String {
+    format!("{}Z", format_date_time(timestamp))
+}
+
+// Seconds since the Unix epoch shifted by `offset_minutes` east of UTC, as
+// "YYYY-MM-DDTHH:MM:SS+HH:MM"
+pub fn format_local(timestamp: i64, offset_minutes: i32) -> String {
+    let sign = if offset_minutes < 0 { '-' } else { '+' };
+    let offset = offset_minutes.unsigned_abs();
+    format!(
+        "{}{}{:02}:{:02}",
+        format_date_time(timestamp + offset_minutes as i64 * 60),
+        sign,
+        offset / 60,
+        offset % 60
+    )
+}
+
+fn format_date_time(timestamp: i64) -> String {
+    let days = timestamp.div_euclid(86400);
+    let seconds = timestamp.rem_euclid(86400);
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+// Days since 1970-01-01 to a proleptic Gregorian (year, month, day)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+// TIME_ZONE_ID_* values from GetTimeZoneInformation
+const TIME_ZONE_ID_STANDARD: u32 = 1;
+const TIME_ZONE_ID_DAYLIGHT: u32 = 2;
+
+// The timezone name and UTC offset (minutes east) in effect when the dump was written.
+// Windows biases are minutes *west* of UTC: UTC = local + bias.
+fn active_timezone(misc_info: &MinidumpMiscInfo) -> Option<(Option<String>, i32)> {
+    let tz = misc_info.raw.time_zone()?;
+    let (name, extra_bias) = match misc_info.raw.time_zone_id().copied() {
+        Some(TIME_ZONE_ID_DAYLIGHT) => (&tz.daylight_name, tz.daylight_bias),
+        Some(TIME_ZONE_ID_STANDARD) => (&tz.standard_name, tz.standard_bias),
+        _ => (&tz.standard_name, 0),
+    };
+
+    let units: Vec<u16> = name.iter().copied().take_while(|&u| u != 0).collect();
+    let name = String::from_utf16_lossy(&units);
+    Some(((!name.is_empty()).then_some(name), -(tz.bias + extra_bias)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{DumpBuilder, put_u16, put_u32};
+    use minidump::Minidump;
+
+    const MISC_INFO_STREAM: u32 = 15;
+    const MINIDUMP_MISC_INFO_3_SIZE: usize = 232;
+    const MINIDUMP_MISC3_TIMEZONE: u32 = 0x40;
+
+    // MINIDUMP_MISC_INFO_3 with only the timezone fields set. Biases are minutes west of UTC.
+    fn misc_info(time_zone_id: u32, bias: i32, daylight_bias: i32) -> Vec<u8> {
+        let mut bytes = vec![0u8; MINIDUMP_MISC_INFO_3_SIZE];
+        put_u32(&mut bytes, 0, MINIDUMP_MISC_INFO_3_SIZE as u32);
+        put_u32(&mut bytes, 4, MINIDUMP_MISC3_TIMEZONE);
+        put_u32(&mut bytes, 56, time_zone_id);
+        let time_zone = 60; // TIME_ZONE_INFORMATION
+        put_u32(&mut bytes, time_zone, bias as u32);
+        for (index, unit) in "Eastern Standard Time".encode_utf16().enumerate() {
+            put_u16(&mut bytes, time_zone + 4 + index * 2, unit);
+        }
+        for (index, unit) in "Eastern Daylight Time".encode_utf16().enumerate() {
+            put_u16(&mut bytes, time_zone + 88 + index * 2, unit);
+        }
+        put_u32(&mut bytes, time_zone + 168, daylight_bias as u32);
+        bytes
+    }
+
+    fn dump_time(timestamp: u32, misc: Option<Vec<u8>>) -> Option<DumpTime> {
+        let mut builder = DumpBuilder::new();
+        if let Some(misc) = misc {
+            builder = builder.stream(MISC_INFO_STREAM, misc);
+        }
+        let bytes = builder.build();
+        let dump = Minidump::read(bytes.as_slice()).unwrap();
+        let misc_info = dump.get_stream::<MinidumpMiscInfo>().ok();
+        build_dump_time(timestamp, misc_info.as_ref())
+    }
+
+    #[test]
+    fn formats_utc() {
+        assert_eq!(format_utc(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_utc(951782400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_utc(1714566896), "2024-05-01T12:34:56Z");
+        assert_eq!(format_utc(u32::MAX as i64), "2106-02-07T06:28:15Z");
+        assert_eq!(format_utc(-1), "1969-12-31T23:59:59Z");
+    }
+
+    #[test]
+    fn formats_local_time_with_offset() {
+        assert_eq!(format_local(1714566896, -240), "2024-05-01T08:34:56-04:00");
+        assert_eq!(format_local(1714566896, 330), "2024-05-01T18:04:56+05:30");
+        assert_eq!(format_local(0, 0), "1970-01-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn reports_local_time_in_the_active_timezone() {
+        let time = dump_time(1714566896, Some(misc_info(TIME_ZONE_ID_DAYLIGHT, 300, -60))).unwrap();
+        assert_eq!(time.utc, "2024-05-01T12:34:56Z");
+        assert_eq!(
+            time.local_time.as_deref(),
+            Some("2024-05-01T08:34:56-04:00")
+        );
+        assert_eq!(time.timezone_name.as_deref(), Some("Eastern Daylight Time"));
+
+        let time = dump_time(1714566896, Some(misc_info(TIME_ZONE_ID_STANDARD, 300, -60))).unwrap();
+        assert_eq!(
+            time.local_time.as_deref(),
+            Some("2024-05-01T07:34:56-05:00")
+        );
+        assert_eq!(time.timezone_name.as_deref(), Some("Eastern Standard Time"));
+    }
+
+    #[test]
+    fn reports_only_utc_without_a_timezone() {
+        let time = dump_time(1714566896, None).unwrap();
+        assert_eq!(time.utc, "2024-05-01T12:34:56Z");
+        assert_eq!(time.local_time, None);
+        assert_eq!(time.timezone_name, None);
+
+        assert!(dump_time(0, Some(misc_info(TIME_ZONE_ID_STANDARD, 300, 0))).is_none());
+    }
+}
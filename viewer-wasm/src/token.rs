@@ -0,0 +1,157 @@
+use crate::common::{SafeU64, read_u32_le, read_u64_le};
+use minidump::{MinidumpHandleDataStream, RawHandleDescriptor};
+use serde::Serialize;
+
+// MINIDUMP_STREAM_TYPE::TokenStream, not parsed by the minidump crate
+pub const TOKEN_STREAM_TYPE: u32 = 19;
+
+// MINIDUMP_TOKEN_INFO_HEADER is four u32 fields:
+// TokenListSize, TokenListEntries, ListHeaderSize, ElementHeaderSize
+const TOKEN_INFO_HEADER_SIZE: usize = 16;
+// MINIDUMP_TOKEN_INFO_LIST is TokenSize (u32), TokenId (u32), TokenHandle (u64),
+// followed by the token data itself
+const TOKEN_INFO_ENTRY_HEADER_SIZE: usize = 16;
+
+#[derive(Serialize)]
+pub struct TokenInfo {
+    // MINIDUMP_TOKEN_INFO_LIST fields
+    pub token_size: u32,
+    pub token_id: u32,
+    pub token_handle: SafeU64,
+
+    // Correlated from the handle data stream, when present
+    pub handle_type: Option<String>,
+    pub handle_object_name: Option<String>,
+}
+
+pub fn parse_token_stream(
+    bytes: &[u8],
+    handles: Option<&MinidumpHandleDataStream>,
+) -> Option<Vec<TokenInfo>> {
+    let entries = read_u32_le(bytes, 4)?;
+    let list_header_size = read_u32_le(bytes, 8)? as usize;
+    let element_header_size = read_u32_le(bytes, 12)? as usize;
+
+    // Guard against headers smaller than the structures we're about to read
+    let list_header_size = list_header_size.max(TOKEN_INFO_HEADER_SIZE);
+    let element_header_size = element_header_size.max(TOKEN_INFO_ENTRY_HEADER_SIZE);
+
+    let mut tokens = Vec::new();
+    let mut offset = list_header_size;
+
+    for _ in 0..entries {
+        let (Some(token_size), Some(token_id), Some(token_handle)) = (
+            read_u32_le(bytes, offset),
+            offset.checked_add(4).and_then(|at| read_u32_le(bytes, at)),
+            offset.checked_add(8).and_then(|at| read_u64_le(bytes, at)),
+        ) else {
+            break;
+        };
+
+        let handle = handles.and_then(|h| {
+            h.iter().find(|descriptor| {
+                let handle_value = match &descriptor.raw {
+                    RawHandleDescriptor::HandleDescriptor(raw) => raw.handle,
+                    RawHandleDescriptor::HandleDescriptor2(raw) => raw.handle,
+                };
+                handle_value == token_handle
+            })
+        });
+
+        tokens.push(TokenInfo {
+            token_size,
+            token_id,
+            token_handle: token_handle.into(),
+            handle_type: handle.and_then(|h| h.type_name.clone()),
+            handle_object_name: handle.and_then(|h| h.object_name.clone()),
+        });
+
+        // TokenSize covers the entry header and its data; never step by less than the header.
+        // On wasm32 a huge TokenSize could wrap the offset back over earlier entries.
+        let Some(next) = offset.checked_add((token_size as usize).max(element_header_size)) else {
+            break;
+        };
+        offset = next;
+    }
+
+    Some(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{DumpBuilder, minidump_string, put_u32, put_u64, stream_rva};
+    use minidump::Minidump;
+
+    const HANDLE_DATA_STREAM_TYPE: u32 = 12;
+
+    // MINIDUMP_TOKEN_INFO_HEADER and one MINIDUMP_TOKEN_INFO_LIST per (id, handle), each
+    // with `data` bytes of token data
+    fn token_stream(element_header_size: u32, tokens: &[(u32, u64)], data: usize) -> Vec<u8> {
+        let entry_size = element_header_size as usize + data;
+        let list_size = TOKEN_INFO_HEADER_SIZE + tokens.len() * entry_size;
+        let mut bytes = vec![0u8; list_size];
+        put_u32(&mut bytes, 0, list_size as u32);
+        put_u32(&mut bytes, 4, tokens.len() as u32);
+        put_u32(&mut bytes, 8, TOKEN_INFO_HEADER_SIZE as u32);
+        put_u32(&mut bytes, 12, element_header_size);
+        for (index, &(id, handle)) in tokens.iter().enumerate() {
+            let entry = TOKEN_INFO_HEADER_SIZE + index * entry_size;
+            put_u32(&mut bytes, entry, entry_size as u32);
+            put_u32(&mut bytes, entry + 4, id);
+            put_u64(&mut bytes, entry + 8, handle);
+        }
+        bytes
+    }
+
+    #[test]
+    fn correlates_tokens_with_handles() {
+        // MINIDUMP_HANDLE_DATA_STREAM with one MINIDUMP_HANDLE_DESCRIPTOR for handle 0x44,
+        // its type name string right after it
+        let mut handles = vec![0u8; 16 + 32];
+        put_u32(&mut handles, 0, 16);
+        put_u32(&mut handles, 4, 32);
+        put_u32(&mut handles, 8, 1);
+        put_u64(&mut handles, 16, 0x44);
+        handles.extend_from_slice(&minidump_string("Token"));
+
+        let mut bytes = DumpBuilder::new()
+            .stream(HANDLE_DATA_STREAM_TYPE, handles)
+            .stream(TOKEN_STREAM_TYPE, Vec::new())
+            .build();
+        let handles_rva = stream_rva(&bytes, HANDLE_DATA_STREAM_TYPE);
+        put_u32(&mut bytes, handles_rva + 24, (handles_rva + 48) as u32); // TypeNameRva
+        let dump = Minidump::read(bytes.as_slice()).unwrap();
+        let handles = dump.get_stream::<MinidumpHandleDataStream>().unwrap();
+
+        let stream = token_stream(16, &[(1, 0x44), (2, 0x48)], 24);
+        let tokens = parse_token_stream(&stream, Some(&handles)).unwrap();
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(tokens[0].token_id, 1);
+        assert_eq!(tokens[0].token_size, 40);
+        assert_eq!(tokens[0].token_handle.raw_value(), 0x44);
+        assert_eq!(tokens[0].handle_type.as_deref(), Some("Token"));
+        assert_eq!(tokens[1].token_id, 2);
+        assert_eq!(tokens[1].token_handle.raw_value(), 0x48);
+        assert_eq!(tokens[1].handle_type, None);
+    }
+
+    #[test]
+    fn steps_by_at_least_the_element_header_and_stops_at_the_end() {
+        // Entries claiming TokenSize 0 still advance by the declared element header size
+        let mut stream = token_stream(24, &[(1, 0x10), (2, 0x20)], 0);
+        put_u32(&mut stream, TOKEN_INFO_HEADER_SIZE, 0);
+        put_u32(&mut stream, 4, 3); // One more entry than the stream holds
+        let tokens = parse_token_stream(&stream, None).unwrap();
+        let ids: Vec<u32> = tokens.iter().map(|token| token.token_id).collect();
+        assert_eq!(ids, [1, 2]);
+
+        // A TokenSize past the end of the stream ends the list after its entry
+        let mut stream = token_stream(16, &[(1, 0x10), (2, 0x20)], 0);
+        put_u32(&mut stream, TOKEN_INFO_HEADER_SIZE, u32::MAX);
+        let tokens = parse_token_stream(&stream, None).unwrap();
+        assert_eq!(tokens.len(), 1);
+
+        assert!(parse_token_stream(&[0; 8], None).is_none());
+    }
+}
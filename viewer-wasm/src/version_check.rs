@@ -0,0 +1,90 @@
+use crate::modules::{normalized_module_name, parse_version_info};
+use minidump::MinidumpModuleList;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Serialize)]
+pub struct ModuleVersionCheck {
+    pub name: String,
+    pub expected: String,
+    pub actual: Option<String>,
+    pub matches: bool,
+}
+
+#[derive(Serialize)]
+pub struct ModuleVersionReport {
+    pub modules: Vec<ModuleVersionCheck>, // Manifest entries, in manifest (sorted) order
+    pub missing_from_manifest: Vec<String>, // Dump modules the manifest doesn't mention
+}
+
+// Compare the dump's module file versions against a manifest of module name -> version.
+// Module names are matched on their normalized name, versions after normalization.
+pub fn check_module_versions(
+    modules: &MinidumpModuleList,
+    expected: &BTreeMap<String, String>,
+) -> ModuleVersionReport {
+    let actual: BTreeMap<String, Option<String>> = modules
+        .iter()
+        .map(|module| {
+            let version = parse_version_info(&module.raw.version_info)
+                .and_then(|v| v.file_version.or(v.product_version));
+            (normalized_module_name(&module.name), version)
+        })
+        .collect();
+
+    let expected: BTreeMap<String, &String> = expected
+        .iter()
+        .map(|(name, version)| (normalized_module_name(name), version))
+        .collect();
+
+    let modules = expected
+        .iter()
+        .map(|(name, expected_version)| {
+            let actual_version = actual.get(name).cloned().flatten();
+            let matches = actual_version
+                .as_deref()
+                .is_some_and(|v| normalize_version(v) == normalize_version(expected_version));
+
+            ModuleVersionCheck {
+                name: name.clone(),
+                expected: expected_version.to_string(),
+                actual: actual_version,
+                matches,
+            }
+        })
+        .collect();
+
+    let missing_from_manifest = actual
+        .keys()
+        .filter(|name| !expected.contains_key(*name))
+        .cloned()
+        .collect();
+
+    ModuleVersionReport {
+        modules,
+        missing_from_manifest,
+    }
+}
+
+// Normalize "v1.02.0.0", "1,2,0,0" and "1.2" to the same numeric components: leading "v"
+// and surrounding whitespace are dropped, components are compared numerically and
+// trailing zero components are ignored
+fn normalize_version(version: &str) -> Vec<String> {
+    let version = version.trim().trim_start_matches(['v', 'V']);
+    let mut parts: Vec<String> = version
+        .split(['.', ','])
+        .map(|part| {
+            let part = part.trim();
+            match part.parse::<u64>() {
+                Ok(number) => number.to_string(),
+                Err(_) => part.to_lowercase(),
+            }
+        })
+        .collect();
+
+    while parts.len() > 1 && parts.last().is_some_and(|p| p == "0") {
+        parts.pop();
+    }
+
+    parts
+}
@@ -0,0 +1,168 @@
+use crate::common::{read_u32_le, read_u64_le};
+use crate::memory::format_memory_size;
+use crate::options::SizeUnits;
+use serde::Serialize;
+
+// MINIDUMP_STREAM_TYPE::ProcessVmCountersStream, not parsed by the minidump crate
+pub const PROCESS_VM_COUNTERS_STREAM_TYPE: u32 = 22;
+
+// MINIDUMP_PROCESS_VM_COUNTERS_1 and _2 sizes
+const VM_COUNTERS_1_SIZE: usize = 80;
+const VM_COUNTERS_2_SIZE: usize = 152;
+
+// MINIDUMP_PROCESS_VM_COUNTERS_2.Flags: which groups of fields were filled in
+const VM_COUNTERS: u16 = 0x0001;
+const VM_COUNTERS_VIRTUALSIZE: u16 = 0x0002;
+const VM_COUNTERS_EX: u16 = 0x0004;
+const VM_COUNTERS_EX2: u16 = 0x0008;
+const VM_COUNTERS_JOB: u16 = 0x0010;
+
+#[derive(Serialize)]
+pub struct MemorySize {
+    pub bytes: u64,
+    pub formatted: String,
+}
+
+#[derive(Serialize)]
+pub struct ProcessVmCounters {
+    pub revision: u16,
+    pub flags: Option<u16>, // Revision 2 only
+
+    // Always present in revision 1; in revision 2 only with the MINIDUMP_PROCESS_VM_COUNTERS
+    // flag
+    pub page_fault_count: Option<u32>,
+    pub peak_working_set_size: Option<MemorySize>,
+    pub working_set_size: Option<MemorySize>,
+    pub quota_peak_paged_pool_usage: Option<MemorySize>,
+    pub quota_paged_pool_usage: Option<MemorySize>,
+    pub quota_peak_non_paged_pool_usage: Option<MemorySize>,
+    pub quota_non_paged_pool_usage: Option<MemorySize>,
+    pub pagefile_usage: Option<MemorySize>,
+    pub peak_pagefile_usage: Option<MemorySize>,
+    pub private_usage: Option<MemorySize>,
+
+    // Revision 2, each present only when its flag is set
+    pub peak_virtual_size: Option<MemorySize>,
+    pub virtual_size: Option<MemorySize>,
+    pub private_working_set_size: Option<MemorySize>,
+    pub shared_commit_usage: Option<MemorySize>,
+    pub job_shared_commit_usage: Option<MemorySize>,
+    pub job_private_commit_usage: Option<MemorySize>,
+    pub job_peak_private_commit_usage: Option<MemorySize>,
+    pub job_private_commit_limit: Option<MemorySize>,
+    pub job_total_commit_limit: Option<MemorySize>,
+}
+
+pub fn parse_process_vm_counters(bytes: &[u8], units: SizeUnits) -> Option<ProcessVmCounters> {
+    let revision = u16::from_le_bytes([*bytes.first()?, *bytes.get(1)?]);
+    let size = |offset: usize| {
+        read_u64_le(bytes, offset).map(|bytes| MemorySize {
+            bytes,
+            formatted: format_memory_size(bytes, units),
+        })
+    };
+
+    match revision {
+        1 if bytes.len() >= VM_COUNTERS_1_SIZE => Some(ProcessVmCounters {
+            revision,
+            flags: None,
+            page_fault_count: read_u32_le(bytes, 4),
+            peak_working_set_size: size(8),
+            working_set_size: size(16),
+            quota_peak_paged_pool_usage: size(24),
+            quota_paged_pool_usage: size(32),
+            quota_peak_non_paged_pool_usage: size(40),
+            quota_non_paged_pool_usage: size(48),
+            pagefile_usage: size(56),
+            peak_pagefile_usage: size(64),
+            private_usage: size(72),
+            peak_virtual_size: None,
+            virtual_size: None,
+            private_working_set_size: None,
+            shared_commit_usage: None,
+            job_shared_commit_usage: None,
+            job_private_commit_usage: None,
+            job_peak_private_commit_usage: None,
+            job_private_commit_limit: None,
+            job_total_commit_limit: None,
+        }),
+        2 if bytes.len() >= VM_COUNTERS_2_SIZE => {
+            let flags = u16::from_le_bytes([bytes[2], bytes[3]]);
+            let gated = |flag: u16, offset: usize| size(offset).filter(|_| flags & flag != 0);
+            Some(ProcessVmCounters {
+                revision,
+                flags: Some(flags),
+                page_fault_count: read_u32_le(bytes, 4).filter(|_| flags & VM_COUNTERS != 0),
+                peak_working_set_size: gated(VM_COUNTERS, 8),
+                working_set_size: gated(VM_COUNTERS, 16),
+                quota_peak_paged_pool_usage: gated(VM_COUNTERS, 24),
+                quota_paged_pool_usage: gated(VM_COUNTERS, 32),
+                quota_peak_non_paged_pool_usage: gated(VM_COUNTERS, 40),
+                quota_non_paged_pool_usage: gated(VM_COUNTERS, 48),
+                pagefile_usage: gated(VM_COUNTERS, 56),
+                peak_pagefile_usage: gated(VM_COUNTERS, 64),
+                peak_virtual_size: gated(VM_COUNTERS_VIRTUALSIZE, 72),
+                virtual_size: gated(VM_COUNTERS_VIRTUALSIZE, 80),
+                private_usage: gated(VM_COUNTERS_EX, 88),
+                private_working_set_size: gated(VM_COUNTERS_EX2, 96),
+                shared_commit_usage: gated(VM_COUNTERS_EX2, 104),
+                job_shared_commit_usage: gated(VM_COUNTERS_JOB, 112),
+                job_private_commit_usage: gated(VM_COUNTERS_JOB, 120),
+                job_peak_private_commit_usage: gated(VM_COUNTERS_JOB, 128),
+                job_private_commit_limit: gated(VM_COUNTERS_JOB, 136),
+                job_total_commit_limit: gated(VM_COUNTERS_JOB, 144),
+            })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A MINIDUMP_PROCESS_VM_COUNTERS_2 with every field set to a distinct value
+    fn counters_2(flags: u16) -> Vec<u8> {
+        let mut bytes = vec![0u8; VM_COUNTERS_2_SIZE];
+        bytes[0..2].copy_from_slice(&2u16.to_le_bytes());
+        bytes[2..4].copy_from_slice(&flags.to_le_bytes());
+        bytes[4..8].copy_from_slice(&7u32.to_le_bytes());
+        for (index, offset) in (8..VM_COUNTERS_2_SIZE).step_by(8).enumerate() {
+            bytes[offset..offset + 8].copy_from_slice(&((index as u64 + 1) * 4096).to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn revision_2_base_counters_need_their_flag() {
+        let counters =
+            parse_process_vm_counters(&counters_2(VM_COUNTERS_VIRTUALSIZE), SizeUnits::Iec)
+                .unwrap();
+        assert_eq!(counters.page_fault_count, None);
+        assert!(counters.working_set_size.is_none());
+        assert!(counters.peak_pagefile_usage.is_none());
+        assert_eq!(counters.virtual_size.unwrap().bytes, 10 * 4096);
+        assert!(counters.private_usage.is_none());
+
+        let counters =
+            parse_process_vm_counters(&counters_2(VM_COUNTERS | VM_COUNTERS_EX), SizeUnits::Iec)
+                .unwrap();
+        assert_eq!(counters.page_fault_count, Some(7));
+        assert_eq!(counters.peak_working_set_size.unwrap().bytes, 4096);
+        assert_eq!(counters.peak_pagefile_usage.unwrap().bytes, 8 * 4096);
+        assert_eq!(counters.private_usage.unwrap().bytes, 11 * 4096);
+        assert!(counters.virtual_size.is_none());
+    }
+
+    #[test]
+    fn revision_1_has_base_counters() {
+        let mut bytes = counters_2(0);
+        bytes.truncate(VM_COUNTERS_1_SIZE);
+        bytes[0..4].copy_from_slice(&1u32.to_le_bytes());
+        let counters = parse_process_vm_counters(&bytes, SizeUnits::Iec).unwrap();
+        assert_eq!(counters.flags, None);
+        assert_eq!(counters.page_fault_count, Some(7));
+        assert_eq!(counters.working_set_size.unwrap().formatted, "8.0 KiB");
+        assert_eq!(counters.private_usage.unwrap().bytes, 9 * 4096);
+    }
+}